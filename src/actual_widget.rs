@@ -1,42 +1,101 @@
 pub mod animated;
 pub mod util;
 
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
-use crate::{event, graphics, layout};
+use crate::{
+    event, graphics,
+    layout::{self, Direction},
+};
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct ActualWidgetId(u64);
 
-pub struct ActualWidgetIdMaker(u64);
+// every maker hands out ids from the same counter so that widgets made outside of App::update (like by a LayoutBuilder during layout) still get unique ids
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+pub struct ActualWidgetIdMaker(());
 impl ActualWidgetIdMaker {
     pub(crate) fn new() -> ActualWidgetIdMaker {
-        ActualWidgetIdMaker(0)
+        ActualWidgetIdMaker(())
     }
     pub(crate) fn next_id(&mut self) -> ActualWidgetId {
-        let id = ActualWidgetId(self.0);
-        self.0 += 1;
-        id
+        ActualWidgetId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+// a way of finding a widget in the tree from outside of it
+#[derive(Copy, Clone, Debug)]
+pub enum WidgetQuery<'a> {
+    Id(ActualWidgetId),
+    // the tag given to a widgets::tag::Tag
+    Tag(&'a str),
+}
+
+impl WidgetQuery<'_> {
+    pub(crate) fn matches_id(&self, id: ActualWidgetId) -> bool {
+        match self {
+            WidgetQuery::Id(query_id) => *query_id == id,
+            WidgetQuery::Tag(_) => false,
+        }
     }
 }
 
 pub trait ActualWidget<Data: ?Sized> {
-    // TODO: automate send_targeted_event by having iter_children_by_z method?
+    // TODO: automate dispatch_event by having iter_children_by_z method?
 
+    // sc.max can be infinite along either axis, but the size that the widget picks always has to be finite
+    // parents call this through layout::layout_child, which checks that in debug builds
     fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints);
+    // whether laying this widget out again with the same constraints as last time could give a different result,
+    // because something that its layout depends on changed since then or an animation is running
+    fn needs_layout(&self) -> bool;
     fn draw(
         &self,
         graphics_context: &graphics::GraphicsContext,
         target: &mut dyn graphics::RenderTarget,
         top_left: graphics::Vector2f,
         hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
     );
 
     fn size(&self) -> graphics::Vector2f;
+    // how far below the top of this widget the baseline of its first line of text is, or None if it has no text
+    // like size, this is only valid after layout
+    fn baseline(&self) -> Option<f32> {
+        None
+    }
+    // the smallest this widget can be along the direction given without cutting off its content, if it is cross_size big along the other direction
+    // these are only estimates for parents that want to size themselves around their children without laying them out first,
+    // and widgets that do not give better ones just go by the size they were last laid out at
+    fn min_intrinsic_size(&self, _: &graphics::GraphicsContext, direction: Direction, _: f32) -> f32 {
+        direction.take_major_component(self.size())
+    }
+    // how big this widget would like to be along the direction given if it had as much space as it wanted
+    fn max_intrinsic_size(&self, _: &graphics::GraphicsContext, direction: Direction, _: f32) -> f32 {
+        direction.take_major_component(self.size())
+    }
 
     fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_>;
+    // focusable widgets in tree order (which is also the tab order)
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_>;
+    // ids of all of the widgets from this one down to the target (inclusive), only including widgets that have ids
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>>;
+    // the rect of the first widget in this subtree that matches the query
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect>;
 
-    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: event::TargetedEvent);
-    fn targeted_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, event: event::TargetedEvent);
-    fn general_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, event: event::GeneralEvent);
+    // finds the widget with the target id and calls handle_event on it
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    );
+    // called once for each phase that the event passes through this widget (see event::route)
+    fn handle_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, event: event::Event, ctx: &mut event::EventContext);
 }
@@ -1,5 +1,7 @@
 use std::time::{Duration, Instant};
 
+use crate::clock;
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Animated<T> {
     last_changed: Instant,
@@ -33,16 +35,17 @@ fn ease(x: f64) -> f64 {
 
 impl<T> Animated<T> {
     pub fn new(item: T) -> Self {
-        Self { last_changed: Instant::now(), current: item, last: None }
+        Self { last_changed: clock::now(), current: item, last: None }
     }
 
     pub(crate) fn get(&self) -> AnimatedValue<T> {
-        if self.last_changed.elapsed() < ANIMATION_DURATION {
+        let elapsed = clock::now().saturating_duration_since(self.last_changed);
+        if elapsed < ANIMATION_DURATION {
             match &self.last {
                 Some(last) => AnimatedValue::Animating {
                     before: last,
                     after: &self.current,
-                    amount: ease(self.last_changed.elapsed().as_secs_f64() / ANIMATION_DURATION.as_secs_f64()),
+                    amount: ease(elapsed.as_secs_f64() / ANIMATION_DURATION.as_secs_f64()),
                 },
                 None => AnimatedValue::Steady(&self.current),
             }
@@ -54,6 +57,10 @@ impl<T> Animated<T> {
     pub(crate) fn get_current(&self) -> &T {
         &self.current
     }
+
+    pub fn is_animating(&self) -> bool {
+        matches!(self.get(), AnimatedValue::Animating { .. })
+    }
 }
 
 impl<T: PartialEq> Animated<T> {
@@ -61,11 +68,16 @@ impl<T: PartialEq> Animated<T> {
         if self.current != new {
             let last = std::mem::replace(&mut self.current, new);
             self.last = Some(last);
-            self.last_changed = Instant::now();
+            self.last_changed = clock::now();
         } else {
             self.current = new;
         }
     }
+
+    pub fn set_immediately(&mut self, new: T) {
+        self.current = new;
+        self.last = None;
+    }
 }
 
 impl<'t, T: Lerpable + Copy> AnimatedValue<'t, T> {
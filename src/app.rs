@@ -0,0 +1,565 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    clock,
+    event::{self, Event},
+    graphics,
+    keymap::{self, Keymap},
+    layout::{self, SizeConstraints},
+    widgets::Widget,
+};
+
+pub struct Settings<Model> {
+    // two clicks of the same button count as a double click if the second one happens within this long after the first one
+    pub double_click_interval: Duration,
+    // and if the mouse has not moved further than this many pixels between them
+    pub double_click_distance: f32,
+    pub keymap: Keymap<Model>,
+}
+
+impl<Model> Default for Settings<Model> {
+    fn default() -> Self {
+        Self { double_click_interval: Duration::from_millis(500), double_click_distance: 4.0, keymap: Keymap::new() }
+    }
+}
+
+// everything that the main loop does except for having a window
+// events go in through the methods here, and nothing happens on its own, so this can be driven by tests without a display
+pub struct App<Model, ModelAsWidget: Widget<Model>, ModelToWidget: Fn(&Model) -> ModelAsWidget> {
+    model: Model,
+    model_to_widget: ModelToWidget,
+    settings: Settings<Model>,
+    graphics_context: graphics::GraphicsContext,
+
+    id_maker: ActualWidgetIdMaker,
+    actual_widget: ModelAsWidget::ActualWidget,
+    viewport_size: graphics::Vector2f,
+
+    // None if the mouse is outside of the viewport
+    mouse_position: Option<graphics::Vector2f>,
+    hovered: HashSet<ActualWidgetId>,
+    cursor: event::CursorShape,
+    focused: Option<ActualWidgetId>,
+    capture: Option<PointerCapture>,
+    last_click: Option<Click>,
+    // the keys that are currently held down, used for telling auto repeats apart from presses and for giving key releases a scancode
+    held_keys: HashMap<sfml::window::Key, event::Scancode>,
+    // the keys pressed so far in a multi key keymap sequence
+    pending_sequence: Vec<(sfml::window::Key, event::Scancode, event::Modifiers)>,
+    // Some for headless apps, which stop time on their thread for as long as they are alive
+    manual_time: Option<clock::ManualTime>,
+}
+
+struct PointerCapture {
+    widget: ActualWidgetId,
+    button: sfml::window::mouse::Button,
+    start: graphics::Vector2f,
+    last: graphics::Vector2f,
+}
+
+struct Click {
+    button: sfml::window::mouse::Button,
+    time: Instant,
+    position: graphics::Vector2f,
+    // how many clicks in a row this was part of
+    count: u32,
+}
+
+// TODO: having this variable doesnt seem right
+const VIEW_TOP_LEFT: graphics::Vector2f = graphics::Vector2f::new(0.0, 0.0);
+
+impl<Model, ModelAsWidget: Widget<Model>, ModelToWidget: Fn(&Model) -> ModelAsWidget> App<Model, ModelAsWidget, ModelToWidget> {
+    pub fn new(
+        graphics_context: graphics::GraphicsContext,
+        settings: Settings<Model>,
+        viewport_size: graphics::Vector2f,
+        model: Model,
+        model_to_widget: ModelToWidget,
+    ) -> Self {
+        let mut id_maker = ActualWidgetIdMaker::new();
+        let actual_widget = model_to_widget(&model).to_actual_widget(&mut id_maker);
+        let mut app = App {
+            model,
+            model_to_widget,
+            settings,
+            graphics_context,
+            id_maker,
+            actual_widget,
+            viewport_size,
+            mouse_position: None,
+            hovered: HashSet::new(),
+            cursor: event::CursorShape::Arrow,
+            focused: None,
+            capture: None,
+            last_click: None,
+            held_keys: HashMap::new(),
+            pending_sequence: Vec::new(),
+            manual_time: None,
+        };
+        app.update();
+        app
+    }
+
+    // loads the system fonts and stops time on this thread so that it only moves forward through advance_time
+    // time starts moving normally again once the app is dropped
+    pub fn new_headless(settings: Settings<Model>, viewport_size: graphics::Vector2f, model: Model, model_to_widget: ModelToWidget) -> Self {
+        let manual_time = clock::ManualTime::start();
+        let mut app = App::new(load_graphics_context(), settings, viewport_size, model, model_to_widget);
+        app.manual_time = Some(manual_time);
+        app
+    }
+
+    pub fn model(&self) -> &Model {
+        &self.model
+    }
+    // changes only show up in the widget tree after the next update
+    pub fn model_mut(&mut self) -> &mut Model {
+        &mut self.model
+    }
+
+    pub fn graphics_context(&self) -> &graphics::GraphicsContext {
+        &self.graphics_context
+    }
+
+    pub fn viewport_size(&self) -> graphics::Vector2f {
+        self.viewport_size
+    }
+    pub fn set_viewport_size(&mut self, viewport_size: graphics::Vector2f) {
+        self.viewport_size = viewport_size;
+    }
+
+    pub fn focused(&self) -> Option<ActualWidgetId> {
+        self.focused
+    }
+    pub fn hovered(&self) -> &HashSet<ActualWidgetId> {
+        &self.hovered
+    }
+    // the cursor that the widget under the mouse asked for
+    pub fn cursor_shape(&self) -> event::CursorShape {
+        self.cursor
+    }
+
+    // where the widget is as of the last update
+    pub fn find_rect(&self, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.actual_widget.find_rect(VIEW_TOP_LEFT, query)
+    }
+
+    // only headless apps control their time, so this does nothing for apps that were not made with new_headless
+    pub fn advance_time(&mut self, duration: Duration) {
+        if let Some(manual_time) = &self.manual_time {
+            manual_time.advance(duration);
+        }
+    }
+
+    // rebuilds the widget tree from the model, lays out the parts of it that changed, and works out which widgets are hovered
+    pub fn update(&mut self) {
+        (self.model_to_widget)(&self.model).update_actual_widget(&mut self.actual_widget, &mut self.id_maker);
+        if self.focused.is_some_and(|focused| self.actual_widget.find_path(focused).is_none()) {
+            // the focused widget is no longer in the tree
+            self.focused = None;
+        }
+
+        layout::layout_child(&mut self.actual_widget, &self.graphics_context, SizeConstraints::loose(self.viewport_size));
+
+        let hovered = {
+            let mut hovered = HashSet::new();
+            if let Some(mouse_position) = self.mouse_position {
+                for (actual_widget, clicks_can_pass_through) in self.actual_widget.find_hover(VIEW_TOP_LEFT, mouse_position) {
+                    hovered.insert(actual_widget);
+                    if !clicks_can_pass_through {
+                        break;
+                    }
+                }
+            }
+            hovered
+        };
+        for left in self.hovered.difference(&hovered) {
+            event::route(&mut self.actual_widget, VIEW_TOP_LEFT, &mut self.model, &[*left], Event::PointerLeave, None);
+        }
+        for entered in hovered.difference(&self.hovered) {
+            event::route(&mut self.actual_widget, VIEW_TOP_LEFT, &mut self.model, &[*entered], Event::PointerEnter, None);
+        }
+        self.hovered = hovered;
+    }
+
+    pub fn draw(&self, target: &mut dyn graphics::RenderTarget) {
+        self.actual_widget.draw(&self.graphics_context, target, VIEW_TOP_LEFT, &self.hovered, self.focused);
+    }
+
+    // sfml mouse events do not say which modifier keys are held down, so the caller has to give them
+    pub fn mouse_down(&mut self, button: sfml::window::mouse::Button, mouse_position: graphics::Vector2f, modifiers: event::Modifiers) {
+        self.mouse_position = Some(mouse_position);
+
+        // click to focus: the innermost focusable widget that the click reaches gets focused
+        let focusable: HashSet<_> = self.actual_widget.find_focusable().collect();
+        self.focused = None;
+        for (hovered, clicks_can_pass_through) in self.actual_widget.find_hover(VIEW_TOP_LEFT, mouse_position) {
+            if focusable.contains(&hovered) {
+                self.focused = Some(hovered);
+            }
+            if !clicks_can_pass_through {
+                break;
+            }
+        }
+
+        let now = clock::now();
+        let click_count = match &self.last_click {
+            Some(last_click)
+                if last_click.button == button
+                    && now.duration_since(last_click.time) <= self.settings.double_click_interval
+                    && distance(last_click.position, mouse_position) <= self.settings.double_click_distance =>
+            {
+                last_click.count + 1
+            }
+            _ => 1,
+        };
+        self.last_click = Some(Click { button, time: now, position: mouse_position, count: click_count });
+
+        let path = hit_path(&self.actual_widget, VIEW_TOP_LEFT, mouse_position);
+        let ctx = event::route(
+            &mut self.actual_widget,
+            VIEW_TOP_LEFT,
+            &mut self.model,
+            &path,
+            Event::MouseDown { button, position: mouse_position, modifiers, click_count },
+            None,
+        );
+        if let (Some(event::CaptureRequest::Capture(widget)), None) = (ctx.and_then(|ctx| ctx.capture_request()), &self.capture) {
+            self.capture = Some(PointerCapture { widget, button, start: mouse_position, last: mouse_position });
+        }
+    }
+
+    pub fn mouse_up(&mut self, button: sfml::window::mouse::Button, mouse_position: graphics::Vector2f, modifiers: event::Modifiers) {
+        self.mouse_position = Some(mouse_position);
+
+        let click_count = self.last_click.as_ref().filter(|last_click| last_click.button == button).map_or(1, |last_click| last_click.count);
+        let (path, drag) = match self.capture.take_if(|capture| capture.button == button) {
+            Some(capture) => (
+                self.actual_widget.find_path(capture.widget).unwrap_or_default(),
+                Some(event::Drag { start: capture.start, position: mouse_position, delta: mouse_position - capture.last, ended: true }),
+            ),
+            None => (hit_path(&self.actual_widget, VIEW_TOP_LEFT, mouse_position), None),
+        };
+        event::route(
+            &mut self.actual_widget,
+            VIEW_TOP_LEFT,
+            &mut self.model,
+            &path,
+            Event::MouseUp { button, position: mouse_position, modifiers, click_count },
+            drag,
+        );
+    }
+
+    pub fn mouse_moved(&mut self, mouse_position: graphics::Vector2f) {
+        self.mouse_position = Some(mouse_position);
+
+        let captured_path = self.capture.as_ref().and_then(|capture| self.actual_widget.find_path(capture.widget));
+        let ctx = match (&mut self.capture, captured_path) {
+            (Some(capture), Some(path)) => {
+                let drag = event::Drag { start: capture.start, position: mouse_position, delta: mouse_position - capture.last, ended: false };
+                capture.last = mouse_position;
+                event::route(&mut self.actual_widget, VIEW_TOP_LEFT, &mut self.model, &path, Event::MouseMoved(mouse_position), Some(drag))
+            }
+            _ => {
+                // the captured widget might have been removed from the tree
+                self.capture = None;
+                let path = hit_path(&self.actual_widget, VIEW_TOP_LEFT, mouse_position);
+                event::route(&mut self.actual_widget, VIEW_TOP_LEFT, &mut self.model, &path, Event::MouseMoved(mouse_position), None)
+            }
+        };
+        if let Some(event::CaptureRequest::Release) = ctx.as_ref().and_then(|ctx| ctx.capture_request()) {
+            self.capture = None;
+        }
+
+        self.cursor = ctx.and_then(|ctx| ctx.requested_cursor()).unwrap_or(event::CursorShape::Arrow);
+    }
+
+    pub fn mouse_left(&mut self) {
+        self.mouse_position = None;
+        self.cursor = event::CursorShape::Arrow;
+    }
+
+    pub fn mouse_wheel_scrolled(&mut self, wheel: sfml::window::mouse::Wheel, delta: f32, mouse_position: graphics::Vector2f) {
+        self.mouse_position = Some(mouse_position);
+
+        let path = hit_path(&self.actual_widget, VIEW_TOP_LEFT, mouse_position);
+        event::route(
+            &mut self.actual_widget,
+            VIEW_TOP_LEFT,
+            &mut self.model,
+            &path,
+            Event::MouseWheelScrolled { wheel, delta, position: mouse_position },
+            None,
+        );
+    }
+
+    pub fn key_pressed(&mut self, code: sfml::window::Key, scan: event::Scancode, modifiers: event::Modifiers) {
+        let repeat = self.held_keys.insert(code, scan).is_some();
+
+        if code == sfml::window::Key::Tab && !modifiers.alt && !modifiers.ctrl && !modifiers.system {
+            let focusable: Vec<_> = self.actual_widget.find_focusable().collect();
+            let current_index = self.focused.and_then(|focused| focusable.iter().position(|id| *id == focused));
+            let new_index = match (current_index, modifiers.shift) {
+                _ if focusable.is_empty() => None,
+                (Some(current_index), false) => Some((current_index + 1) % focusable.len()),
+                (Some(current_index), true) => Some((current_index + focusable.len() - 1) % focusable.len()),
+                (None, false) => Some(0),
+                (None, true) => Some(focusable.len() - 1),
+            };
+            self.focused = new_index.map(|new_index| focusable[new_index]);
+            return;
+        }
+
+        let path = focus_path(&self.actual_widget, self.focused);
+        let ctx =
+            event::route(&mut self.actual_widget, VIEW_TOP_LEFT, &mut self.model, &path, Event::KeyPressed { code, scan, modifiers, repeat }, None);
+
+        // the keymap only gets the keys that the widgets did not want
+        if ctx.as_ref().is_some_and(|ctx| ctx.is_handled()) {
+            self.pending_sequence.clear();
+        } else if !keymap::is_modifier_key(code) {
+            let contexts = ctx.as_ref().map_or(&[][..], |ctx| ctx.keymap_contexts());
+            self.pending_sequence.push((code, scan, modifiers));
+            let mut result = self.settings.keymap.handle_sequence(&self.pending_sequence, contexts, &mut self.model);
+            if let keymap::SequenceResult::NoMatch = result {
+                // this key might not continue the sequence but it might still start a new one
+                if self.pending_sequence.len() > 1 {
+                    self.pending_sequence = vec![(code, scan, modifiers)];
+                    result = self.settings.keymap.handle_sequence(&self.pending_sequence, contexts, &mut self.model);
+                }
+            }
+            match result {
+                keymap::SequenceResult::Pending => {}
+                keymap::SequenceResult::Ran | keymap::SequenceResult::NoMatch => self.pending_sequence.clear(),
+            }
+        }
+    }
+
+    pub fn key_released(&mut self, code: sfml::window::Key, modifiers: event::Modifiers) {
+        // sfml does not give the scancode for releases
+        let scan = self.held_keys.remove(&code).unwrap_or(event::Scancode::Unknown);
+        let path = focus_path(&self.actual_widget, self.focused);
+        event::route(&mut self.actual_widget, VIEW_TOP_LEFT, &mut self.model, &path, Event::KeyReleased { code, scan, modifiers }, None);
+    }
+
+    pub fn text_entered(&mut self, ch: char) {
+        let path = focus_path(&self.actual_widget, self.focused);
+        event::route(&mut self.actual_widget, VIEW_TOP_LEFT, &mut self.model, &path, Event::TextEntered(ch), None);
+    }
+
+    // the releases of keys that are held down while the window is not focused will never be seen
+    pub fn lost_focus(&mut self) {
+        self.held_keys.clear();
+    }
+}
+
+pub(crate) fn load_graphics_context() -> graphics::GraphicsContext {
+    let fonts = {
+        // TODO: don't panic?
+        let text_font_handle = font_kit::source::SystemSource::new()
+            .select_best_match(
+                &[font_kit::family_name::FamilyName::SansSerif, font_kit::family_name::FamilyName::Serif],
+                &font_kit::properties::Properties::new(),
+            )
+            .expect("could not find appropriate text font font");
+        let text_font = match text_font_handle {
+            font_kit::handle::Handle::Path { path, font_index: _ } => {
+                sfml::graphics::Font::from_file(&path.to_string_lossy()).expect("could not load font")
+            } // TODO: figure out how to handle font_index
+            font_kit::handle::Handle::Memory { bytes: _, font_index: _ } => unimplemented!("loading font from memory"),
+        };
+
+        let monospace_font_handle = font_kit::source::SystemSource::new()
+            .select_best_match(&[font_kit::family_name::FamilyName::Monospace], &font_kit::properties::Properties::new())
+            .expect("could not find appropriate monospace font");
+        let monospace_font = match monospace_font_handle {
+            font_kit::handle::Handle::Path { path, font_index: _ } => {
+                sfml::graphics::Font::from_file(&path.to_string_lossy()).expect("could not load font")
+            } // TODO: figure out how to handle font_index
+            font_kit::handle::Handle::Memory { bytes: _, font_index: _ } => unimplemented!("loading font from memory"),
+        };
+
+        graphics::Fonts { text_font, monospace_font }
+    };
+
+    graphics::GraphicsContext {
+        default_render_context_settings: sfml::window::ContextSettings { antialiasing_level: 0, ..Default::default() },
+        fonts,
+    }
+}
+
+fn distance(a: graphics::Vector2f, b: graphics::Vector2f) -> f32 {
+    let d = a - b;
+    (d.x * d.x + d.y * d.y).sqrt()
+}
+
+// mouse events go to the innermost hovered widget that the mouse can reach
+fn hit_path<Data>(actual_widget: &dyn ActualWidget<Data>, top_left: graphics::Vector2f, mouse_position: graphics::Vector2f) -> Vec<ActualWidgetId> {
+    let mut target = None;
+    for (hovered, clicks_can_pass_through) in actual_widget.find_hover(top_left, mouse_position) {
+        target = Some(hovered);
+        if !clicks_can_pass_through {
+            break;
+        }
+    }
+    target.and_then(|target| actual_widget.find_path(target)).unwrap_or_default()
+}
+
+// keyboard events go to the focused widget
+fn focus_path<Data>(actual_widget: &dyn ActualWidget<Data>, focused: Option<ActualWidgetId>) -> Vec<ActualWidgetId> {
+    focused.and_then(|focused| actual_widget.find_path(focused)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sfml::window::{mouse::Button, Key};
+
+    use crate::{
+        actual_widget::WidgetQuery,
+        event, graphics,
+        widgets::{
+            clickable::{Clickable, MouseButton},
+            flex::ItemSettings,
+            tag::Tag,
+            test_rect::TestRect,
+            text_field::TextField,
+            Widget,
+        },
+        App, Settings,
+    };
+
+    #[derive(Default)]
+    struct Model {
+        clicks: u32,
+        double_clicks: u32,
+        text: String,
+    }
+
+    const VIEWPORT_SIZE: graphics::Vector2f = graphics::Vector2f::new(200.0, 200.0);
+
+    fn to_widget(model: &Model) -> impl Widget<Model> {
+        let rect = || TestRect::new(graphics::Color::WHITE, graphics::Vector2f::new(50.0, 20.0));
+        crate::flex!(vertical {
+            button: (
+                ItemSettings::fixed(),
+                Tag::new("button", Clickable::new(MouseButton::Left, |model: &mut Model| model.clicks += 1, rect(), rect()))
+            ),
+            double: (
+                ItemSettings::fixed(),
+                Tag::new("double", Clickable::new_double_click(MouseButton::Left, |model: &mut Model| model.double_clicks += 1, rect(), rect()))
+            ),
+            field: (
+                ItemSettings::fixed(),
+                Tag::new("field", TextField::new(model.text.clone(), graphics::Fonts::text_font, 16, |model: &mut Model| &mut model.text))
+            ),
+        })
+    }
+
+    fn center_of<Model, ModelAsWidget: Widget<Model>, ModelToWidget: Fn(&Model) -> ModelAsWidget>(
+        app: &App<Model, ModelAsWidget, ModelToWidget>,
+        tag: &str,
+    ) -> graphics::Vector2f {
+        let rect = app.find_rect(WidgetQuery::Tag(tag)).expect("tagged widget should be in the tree");
+        graphics::Vector2f::new(rect.left + rect.width / 2.0, rect.top + rect.height / 2.0)
+    }
+
+    fn click<Model, ModelAsWidget: Widget<Model>, ModelToWidget: Fn(&Model) -> ModelAsWidget>(
+        app: &mut App<Model, ModelAsWidget, ModelToWidget>,
+        position: graphics::Vector2f,
+    ) {
+        app.mouse_down(Button::Left, position, event::Modifiers::default());
+        app.update();
+        app.mouse_up(Button::Left, position, event::Modifiers::default());
+        app.update();
+    }
+
+    #[test]
+    fn click_calls_on_click() {
+        let mut app = App::new_headless(Settings::default(), VIEWPORT_SIZE, Model::default(), to_widget);
+        let button = center_of(&app, "button");
+        click(&mut app, button);
+        assert_eq!(app.model().clicks, 1);
+        click(&mut app, button);
+        assert_eq!(app.model().clicks, 2);
+        assert_eq!(app.model().double_clicks, 0);
+    }
+
+    #[test]
+    fn double_click_only_counts_within_interval() {
+        let mut app = App::new_headless(Settings::default(), VIEWPORT_SIZE, Model::default(), to_widget);
+        let double = center_of(&app, "double");
+
+        click(&mut app, double);
+        app.advance_time(Duration::from_millis(100));
+        click(&mut app, double);
+        assert_eq!(app.model().double_clicks, 1);
+
+        app.advance_time(Duration::from_secs(1));
+        click(&mut app, double);
+        app.advance_time(Duration::from_secs(1));
+        click(&mut app, double);
+        assert_eq!(app.model().double_clicks, 1);
+    }
+
+    #[test]
+    fn hover_follows_mouse() {
+        let mut app = App::new_headless(Settings::default(), VIEWPORT_SIZE, Model::default(), to_widget);
+        let button = center_of(&app, "button");
+        assert!(app.hovered().is_empty());
+
+        app.mouse_moved(button);
+        app.update();
+        assert!(!app.hovered().is_empty());
+        for hovered in app.hovered() {
+            assert!(app.find_rect(WidgetQuery::Id(*hovered)).expect("hovered widget should be in the tree").contains(button));
+        }
+
+        app.mouse_left();
+        app.update();
+        assert!(app.hovered().is_empty());
+    }
+
+    #[test]
+    fn tab_focuses_text_field() {
+        let mut app = App::new_headless(Settings::default(), VIEWPORT_SIZE, Model::default(), to_widget);
+        assert_eq!(app.focused(), None);
+
+        app.key_pressed(Key::Tab, event::Scancode::Tab, event::Modifiers::default());
+        app.update();
+        let focused = app.focused().expect("tab should focus the text field");
+        assert_eq!(app.find_rect(WidgetQuery::Id(focused)), app.find_rect(WidgetQuery::Tag("field")));
+    }
+
+    #[test]
+    fn typing_into_clicked_text_field() {
+        let mut app = App::new_headless(Settings::default(), VIEWPORT_SIZE, Model { text: "abc".to_string(), ..Model::default() }, to_widget);
+        let field = center_of(&app, "field");
+
+        click(&mut app, field);
+        assert!(app.focused().is_some());
+
+        // the caret starts at the end of the text
+        app.key_pressed(Key::End, event::Scancode::End, event::Modifiers::default());
+        app.update();
+        app.key_pressed(Key::Backspace, event::Scancode::Backspace, event::Modifiers::default());
+        app.update();
+        assert_eq!(app.model().text, "ab");
+        app.text_entered('d');
+        app.update();
+        assert_eq!(app.model().text, "abd");
+
+        // clicking somewhere that is not focusable takes the focus away
+        let button = center_of(&app, "button");
+        click(&mut app, button);
+        assert_eq!(app.focused(), None);
+        app.text_entered('e');
+        app.update();
+        assert_eq!(app.model().text, "abd");
+    }
+}
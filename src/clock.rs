@@ -0,0 +1,37 @@
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+// everything that depends on time (animations, double clicks, ...) gets the time from here so that headless apps can control it
+thread_local! {
+    static MANUAL_NOW: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+pub(crate) fn now() -> Instant {
+    MANUAL_NOW.get().unwrap_or_else(Instant::now)
+}
+
+// while this is alive, time on this thread only moves forward through advance
+// dropping it puts the clock back to how it was before, so a headless app does not leave time frozen for whatever runs on the thread next
+pub(crate) struct ManualTime {
+    previous: Option<Instant>,
+}
+
+impl ManualTime {
+    pub(crate) fn start() -> ManualTime {
+        let previous = MANUAL_NOW.get();
+        MANUAL_NOW.set(Some(now()));
+        ManualTime { previous }
+    }
+
+    pub(crate) fn advance(&self, duration: Duration) {
+        MANUAL_NOW.set(Some(now() + duration));
+    }
+}
+
+impl Drop for ManualTime {
+    fn drop(&mut self) {
+        MANUAL_NOW.set(self.previous);
+    }
+}
@@ -1,14 +1,252 @@
-use crate::graphics;
+use crate::{
+    actual_widget::{ActualWidget, ActualWidgetId},
+    graphics,
+};
+
+mod scancode;
+
+pub use scancode::Scancode;
 
 #[derive(Copy, Clone)]
-pub enum TargetedEvent {
-    LeftMouseDown(graphics::Vector2f),
-    RightMouseDown(graphics::Vector2f),
+pub enum Event {
+    // click_count is 1 for a single click, 2 for a double click, and so on
+    MouseDown { button: sfml::window::mouse::Button, position: graphics::Vector2f, modifiers: Modifiers, click_count: u32 },
+    MouseMoved(graphics::Vector2f),
+    // click_count is the same as the click_count of the mouse down event that this ends
+    MouseUp { button: sfml::window::mouse::Button, position: graphics::Vector2f, modifiers: Modifiers, click_count: u32 },
+    // repeat is true if this is an auto repeat from the key being held down
+    KeyPressed { code: sfml::window::Key, scan: Scancode, modifiers: Modifiers, repeat: bool },
+    KeyReleased { code: sfml::window::Key, scan: Scancode, modifiers: Modifiers },
+    TextEntered(char),
+    MouseWheelScrolled { wheel: sfml::window::mouse::Wheel, delta: f32, position: graphics::Vector2f },
+    // these are only ever delivered in the target phase
+    PointerEnter,
+    PointerLeave,
+}
+
+// which modifier keys were held down when a mouse or keyboard event happened
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Modifiers {
+    pub alt: bool,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub system: bool,
+}
+
+// a key is either identified by what the keyboard layout says it is or by where it physically is on the keyboard
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ChordKey {
+    Code(sfml::window::Key),
+    Scan(Scancode),
+}
+
+// a key together with the exact set of modifiers that have to be held down with it, like ctrl+shift+s
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct KeyChord {
+    pub key: ChordKey,
+    pub modifiers: Modifiers,
+}
+
+impl KeyChord {
+    pub fn new(key: sfml::window::Key) -> KeyChord {
+        KeyChord { key: ChordKey::Code(key), modifiers: Modifiers::default() }
+    }
+    pub fn new_scancode(scan: Scancode) -> KeyChord {
+        KeyChord { key: ChordKey::Scan(scan), modifiers: Modifiers::default() }
+    }
+
+    pub fn alt(mut self) -> KeyChord {
+        self.modifiers.alt = true;
+        self
+    }
+    pub fn ctrl(mut self) -> KeyChord {
+        self.modifiers.ctrl = true;
+        self
+    }
+    pub fn shift(mut self) -> KeyChord {
+        self.modifiers.shift = true;
+        self
+    }
+    pub fn system(mut self) -> KeyChord {
+        self.modifiers.system = true;
+        self
+    }
+
+    pub fn matches(&self, code: sfml::window::Key, scan: Scancode, modifiers: Modifiers) -> bool {
+        let key_matches = match self.key {
+            ChordKey::Code(key) => key == code,
+            ChordKey::Scan(key_scan) => key_scan == scan,
+        };
+        key_matches && modifiers == self.modifiers
+    }
+}
+
+impl std::fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.modifiers.system {
+            write!(f, "System+")?;
+        }
+        match self.key {
+            ChordKey::Code(key) => write!(f, "{key:?}"),
+            ChordKey::Scan(scan) => write!(f, "{scan:?}"),
+        }
+    }
+}
+
+// widgets can request one of these while handling MouseMoved, and it will be shown until the mouse moves again
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CursorShape {
+    Arrow,
+    Hand,
+    IBeam,
+    ResizeHorizontal,
+    ResizeVertical,
 }
+
+// events first travel down from the root to the target (capture), then get delivered to the target itself, and then travel back up to the root (bubble)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Phase {
+    Capture,
+    Target,
+    Bubble,
+}
+
+// information about the drag that is happening while a widget has captured the pointer
+#[derive(Copy, Clone, Debug)]
+pub struct Drag {
+    // where the mouse button was pressed
+    pub start: graphics::Vector2f,
+    pub position: graphics::Vector2f,
+    // how far the mouse moved since the last event in this drag
+    pub delta: graphics::Vector2f,
+    // true if this is the mouse up event that ends the drag
+    pub ended: bool,
+}
+
 #[derive(Copy, Clone)]
-pub enum GeneralEvent {
-    MouseMoved(graphics::Vector2f),
-    LeftMouseUp,
-    RightMouseUp,
-    KeyPressed { code: sfml::window::Key, alt: bool, ctrl: bool, shift: bool, system: bool },
+pub(crate) enum CaptureRequest {
+    Capture(ActualWidgetId),
+    Release,
+}
+
+pub struct EventContext {
+    phase: Phase,
+    target: ActualWidgetId,
+    current: ActualWidgetId,
+    handled: bool,
+
+    drag: Option<Drag>,
+    capture_request: Option<CaptureRequest>,
+    cursor: Option<CursorShape>,
+    keymap_contexts: Vec<&'static str>,
+}
+
+impl EventContext {
+    fn new(target: ActualWidgetId, drag: Option<Drag>) -> EventContext {
+        EventContext {
+            phase: Phase::Capture,
+            target,
+            current: target,
+            handled: false,
+            drag,
+            capture_request: None,
+            cursor: None,
+            keymap_contexts: Vec::new(),
+        }
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn target(&self) -> ActualWidgetId {
+        self.target
+    }
+
+    // Some if the widget this event is going to has captured the pointer
+    pub fn drag(&self) -> Option<Drag> {
+        self.drag
+    }
+
+    // makes all mouse events go to the widget that is currently handling this event until the mouse button that started it is released
+    // (only has any effect when handling a mouse down event)
+    pub fn capture_pointer(&mut self) {
+        self.capture_request = Some(CaptureRequest::Capture(self.current));
+    }
+
+    pub fn release_pointer(&mut self) {
+        self.capture_request = Some(CaptureRequest::Release);
+    }
+
+    pub(crate) fn capture_request(&self) -> Option<CaptureRequest> {
+        self.capture_request
+    }
+
+    // only the first request counts so that the innermost widget wins as long as widgets do this in the target and bubble phases
+    pub fn request_cursor(&mut self, shape: CursorShape) {
+        if self.cursor.is_none() {
+            self.cursor = Some(shape);
+        }
+    }
+
+    pub(crate) fn requested_cursor(&self) -> Option<CursorShape> {
+        self.cursor
+    }
+
+    pub(crate) fn enter_keymap_context(&mut self, name: &'static str) {
+        self.keymap_contexts.push(name);
+    }
+
+    // the names of the KeymapContexts that the event passed through on the way to its target, from outermost to innermost
+    pub(crate) fn keymap_contexts(&self) -> &[&'static str] {
+        &self.keymap_contexts
+    }
+
+    // stops the event from being delivered to any more widgets
+    pub fn set_handled(&mut self) {
+        self.handled = true;
+    }
+
+    pub fn is_handled(&self) -> bool {
+        self.handled
+    }
+}
+
+// delivers an event along a path from the root to the target (the path should come from ActualWidget::find_path)
+// returns the context so that the caller can see whether the event was handled and what the widgets requested
+pub(crate) fn route<Data: ?Sized>(
+    root: &mut dyn ActualWidget<Data>,
+    top_left: graphics::Vector2f,
+    data: &mut Data,
+    path: &[ActualWidgetId],
+    event: Event,
+    drag: Option<Drag>,
+) -> Option<EventContext> {
+    let (&target, ancestors) = path.split_last()?;
+    let mut ctx = EventContext::new(target, drag);
+
+    let phases = ancestors
+        .iter()
+        .map(|id| (Phase::Capture, *id))
+        .chain(std::iter::once((Phase::Target, target)))
+        .chain(ancestors.iter().rev().map(|id| (Phase::Bubble, *id)));
+    for (phase, id) in phases {
+        ctx.phase = phase;
+        ctx.current = id;
+        root.dispatch_event(top_left, data, id, event, &mut ctx);
+        if ctx.handled {
+            break;
+        }
+    }
+
+    Some(ctx)
 }
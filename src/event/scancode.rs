@@ -0,0 +1,309 @@
+// sfml does not export its scancode type so this is a copy of it
+// scancodes identify keys by their physical position on the keyboard (as if it had a us layout) instead of by what the keyboard layout says they are
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Scancode {
+    Unknown,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Num1,
+    Num2,
+    Num3,
+    Num4,
+    Num5,
+    Num6,
+    Num7,
+    Num8,
+    Num9,
+    Num0,
+    Enter,
+    Escape,
+    Backspace,
+    Tab,
+    Space,
+    Hyphen,
+    Equal,
+    LBracket,
+    RBracket,
+    Backslash,
+    Semicolon,
+    Apostrophe,
+    Grave,
+    Comma,
+    Period,
+    Slash,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    CapsLock,
+    PrintScreen,
+    ScrollLock,
+    Pause,
+    Insert,
+    Home,
+    PageUp,
+    Delete,
+    End,
+    PageDown,
+    Right,
+    Left,
+    Down,
+    Up,
+    NumLock,
+    NumpadDivide,
+    NumpadMultiply,
+    NumpadMinus,
+    NumpadPlus,
+    NumpadEqual,
+    NumpadEnter,
+    NumpadDecimal,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    Numpad0,
+    NonUsBackslash,
+    Application,
+    Execute,
+    ModeChange,
+    Help,
+    Menu,
+    Select,
+    Redo,
+    Undo,
+    Cut,
+    Copy,
+    Paste,
+    VolumeMute,
+    VolumeUp,
+    VolumeDown,
+    MediaPlayPause,
+    MediaStop,
+    MediaNextTrack,
+    MediaPreviousTrack,
+    LControl,
+    LShift,
+    LAlt,
+    LSystem,
+    RControl,
+    RShift,
+    RAlt,
+    RSystem,
+    Back,
+    Forward,
+    Refresh,
+    Stop,
+    Search,
+    Favorites,
+    HomePage,
+    LaunchApplication1,
+    LaunchApplication2,
+    LaunchMail,
+    LaunchMediaSelect,
+}
+
+// in the same order as sfml's scancodes so that they can be converted by index
+const ALL: [Scancode; 146] = [
+    Scancode::A,
+    Scancode::B,
+    Scancode::C,
+    Scancode::D,
+    Scancode::E,
+    Scancode::F,
+    Scancode::G,
+    Scancode::H,
+    Scancode::I,
+    Scancode::J,
+    Scancode::K,
+    Scancode::L,
+    Scancode::M,
+    Scancode::N,
+    Scancode::O,
+    Scancode::P,
+    Scancode::Q,
+    Scancode::R,
+    Scancode::S,
+    Scancode::T,
+    Scancode::U,
+    Scancode::V,
+    Scancode::W,
+    Scancode::X,
+    Scancode::Y,
+    Scancode::Z,
+    Scancode::Num1,
+    Scancode::Num2,
+    Scancode::Num3,
+    Scancode::Num4,
+    Scancode::Num5,
+    Scancode::Num6,
+    Scancode::Num7,
+    Scancode::Num8,
+    Scancode::Num9,
+    Scancode::Num0,
+    Scancode::Enter,
+    Scancode::Escape,
+    Scancode::Backspace,
+    Scancode::Tab,
+    Scancode::Space,
+    Scancode::Hyphen,
+    Scancode::Equal,
+    Scancode::LBracket,
+    Scancode::RBracket,
+    Scancode::Backslash,
+    Scancode::Semicolon,
+    Scancode::Apostrophe,
+    Scancode::Grave,
+    Scancode::Comma,
+    Scancode::Period,
+    Scancode::Slash,
+    Scancode::F1,
+    Scancode::F2,
+    Scancode::F3,
+    Scancode::F4,
+    Scancode::F5,
+    Scancode::F6,
+    Scancode::F7,
+    Scancode::F8,
+    Scancode::F9,
+    Scancode::F10,
+    Scancode::F11,
+    Scancode::F12,
+    Scancode::F13,
+    Scancode::F14,
+    Scancode::F15,
+    Scancode::F16,
+    Scancode::F17,
+    Scancode::F18,
+    Scancode::F19,
+    Scancode::F20,
+    Scancode::F21,
+    Scancode::F22,
+    Scancode::F23,
+    Scancode::F24,
+    Scancode::CapsLock,
+    Scancode::PrintScreen,
+    Scancode::ScrollLock,
+    Scancode::Pause,
+    Scancode::Insert,
+    Scancode::Home,
+    Scancode::PageUp,
+    Scancode::Delete,
+    Scancode::End,
+    Scancode::PageDown,
+    Scancode::Right,
+    Scancode::Left,
+    Scancode::Down,
+    Scancode::Up,
+    Scancode::NumLock,
+    Scancode::NumpadDivide,
+    Scancode::NumpadMultiply,
+    Scancode::NumpadMinus,
+    Scancode::NumpadPlus,
+    Scancode::NumpadEqual,
+    Scancode::NumpadEnter,
+    Scancode::NumpadDecimal,
+    Scancode::Numpad1,
+    Scancode::Numpad2,
+    Scancode::Numpad3,
+    Scancode::Numpad4,
+    Scancode::Numpad5,
+    Scancode::Numpad6,
+    Scancode::Numpad7,
+    Scancode::Numpad8,
+    Scancode::Numpad9,
+    Scancode::Numpad0,
+    Scancode::NonUsBackslash,
+    Scancode::Application,
+    Scancode::Execute,
+    Scancode::ModeChange,
+    Scancode::Help,
+    Scancode::Menu,
+    Scancode::Select,
+    Scancode::Redo,
+    Scancode::Undo,
+    Scancode::Cut,
+    Scancode::Copy,
+    Scancode::Paste,
+    Scancode::VolumeMute,
+    Scancode::VolumeUp,
+    Scancode::VolumeDown,
+    Scancode::MediaPlayPause,
+    Scancode::MediaStop,
+    Scancode::MediaNextTrack,
+    Scancode::MediaPreviousTrack,
+    Scancode::LControl,
+    Scancode::LShift,
+    Scancode::LAlt,
+    Scancode::LSystem,
+    Scancode::RControl,
+    Scancode::RShift,
+    Scancode::RAlt,
+    Scancode::RSystem,
+    Scancode::Back,
+    Scancode::Forward,
+    Scancode::Refresh,
+    Scancode::Stop,
+    Scancode::Search,
+    Scancode::Favorites,
+    Scancode::HomePage,
+    Scancode::LaunchApplication1,
+    Scancode::LaunchApplication2,
+    Scancode::LaunchMail,
+    Scancode::LaunchMediaSelect,
+];
+
+impl Scancode {
+    // takes the sfml scancode converted to an integer because the sfml type cannot be named
+    pub(crate) fn from_sfml(sfml_scancode: i32) -> Scancode {
+        usize::try_from(sfml_scancode).ok().and_then(|index| ALL.get(index)).copied().unwrap_or(Scancode::Unknown)
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt::Display;
+
+use crate::event::{self, KeyChord};
+
+pub struct Command<Model> {
+    pub name: &'static str,
+    pub description: &'static str,
+    action: Box<dyn Fn(&mut Model)>,
+}
+
+pub struct Binding {
+    // the chords that have to be pressed one after the other, like ctrl+k ctrl+s
+    pub sequence: Vec<KeyChord>,
+    pub command: &'static str,
+    // None if this binding applies everywhere, otherwise it only applies when the focused widget is inside of a KeymapContext with this name
+    pub context: Option<&'static str>,
+}
+
+// keyboard shortcuts for the whole app
+// bindings only get used for key presses that none of the widgets handled
+pub struct Keymap<Model> {
+    commands: Vec<Command<Model>>,
+    bindings: Vec<Binding>,
+}
+
+pub(crate) enum SequenceResult {
+    // the sequence matched a binding and its command was run
+    Ran,
+    // the sequence is the start of at least one binding so more chords need to be pressed
+    Pending,
+    NoMatch,
+}
+
+impl<Model> Keymap<Model> {
+    pub fn new() -> Self {
+        Self { commands: Vec::new(), bindings: Vec::new() }
+    }
+
+    pub fn command(mut self, name: &'static str, description: &'static str, action: impl Fn(&mut Model) + 'static) -> Self {
+        assert!(self.find_command(name).is_none(), "command {name:?} registered twice");
+        self.commands.push(Command { name, description, action: Box::new(action) });
+        self
+    }
+
+    pub fn bind(self, sequence: &[KeyChord], command: &'static str) -> Self {
+        self.add_binding(sequence, command, None)
+    }
+
+    pub fn bind_in_context(self, context: &'static str, sequence: &[KeyChord], command: &'static str) -> Self {
+        self.add_binding(sequence, command, Some(context))
+    }
+
+    fn add_binding(mut self, sequence: &[KeyChord], command: &'static str, context: Option<&'static str>) -> Self {
+        assert!(!sequence.is_empty(), "binding for command {command:?} has no keys");
+        assert!(self.find_command(command).is_some(), "binding to unknown command {command:?}");
+        self.bindings.push(Binding { sequence: sequence.to_vec(), command, context });
+        self
+    }
+
+    pub fn commands(&self) -> &[Command<Model>] {
+        &self.commands
+    }
+
+    // for showing in help screens
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    pub fn bindings_for_command(&self, command: &'static str) -> impl Iterator<Item = &Binding> + '_ {
+        self.bindings.iter().filter(move |binding| binding.command == command)
+    }
+
+    // returns false if there is no command with this name
+    pub fn run_command(&self, name: &str, model: &mut Model) -> bool {
+        match self.find_command(name) {
+            Some(command) => {
+                (command.action)(model);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn find_command(&self, name: &str) -> Option<&Command<Model>> {
+        self.commands.iter().find(|command| command.name == name)
+    }
+
+    // contexts are the names of the KeymapContexts that the focused widget is in, from outermost to innermost
+    // bindings in inner contexts take priority over bindings in outer contexts, which take priority over bindings that apply everywhere
+    pub(crate) fn handle_sequence(
+        &self,
+        sequence: &[(sfml::window::Key, event::Scancode, event::Modifiers)],
+        contexts: &[&'static str],
+        model: &mut Model,
+    ) -> SequenceResult {
+        let priority = |binding: &Binding| match binding.context {
+            None => Some(0),
+            Some(context) => contexts.iter().rposition(|active| *active == context).map(|index| index + 1),
+        };
+        let sequence_matches =
+            |binding: &Binding| binding.sequence.iter().zip(sequence).all(|(chord, (code, scan, modifiers))| chord.matches(*code, *scan, *modifiers));
+
+        let mut best_exact = None;
+        let mut any_pending = false;
+        for binding in &self.bindings {
+            let Some(priority) = priority(binding) else { continue };
+            if binding.sequence.len() < sequence.len() || !sequence_matches(binding) {
+                continue;
+            }
+
+            if binding.sequence.len() == sequence.len() {
+                if best_exact.is_none_or(|(best_priority, _)| priority > best_priority) {
+                    best_exact = Some((priority, binding));
+                }
+            } else {
+                any_pending = true;
+            }
+        }
+
+        match best_exact {
+            Some((_, binding)) => {
+                self.run_command(binding.command, model);
+                SequenceResult::Ran
+            }
+            None if any_pending => SequenceResult::Pending,
+            None => SequenceResult::NoMatch,
+        }
+    }
+}
+
+impl<Model> Default for Keymap<Model> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, chord) in self.sequence.iter().enumerate() {
+            if i != 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{chord}")?;
+        }
+        Ok(())
+    }
+}
+
+// keys that only ever act as modifiers and so cannot start or continue a sequence by themselves
+pub(crate) fn is_modifier_key(code: sfml::window::Key) -> bool {
+    use sfml::window::Key;
+    matches!(code, Key::LControl | Key::RControl | Key::LShift | Key::RShift | Key::LAlt | Key::RAlt | Key::LSystem | Key::RSystem)
+}
@@ -1,13 +1,76 @@
-use crate::graphics;
+use std::cell::Cell;
 
-#[derive(Copy, Clone, PartialEq)]
+use crate::{actual_widget::ActualWidget, graphics};
+
+thread_local! {
+    static LAYOUT_COUNT: Cell<u64> = const { Cell::new(0) };
+}
+
+// how many times a widget has been laid out on this thread, not counting layouts that were skipped because nothing changed
+pub fn layout_count() -> u64 {
+    LAYOUT_COUNT.get()
+}
+
+// parents lay out their children through this, which checks in debug builds that the child picked a size that the constraints allow
+// this is where the check is instead of in each widget's layout so that widgets without a LayoutCache are checked too
+pub fn layout_child<Data: ?Sized>(child: &mut (impl ActualWidget<Data> + ?Sized), graphics_context: &graphics::GraphicsContext, sc: SizeConstraints) {
+    debug_assert!(sc.is_valid(), "widget was laid out with invalid constraints {sc:?}");
+    child.layout(graphics_context, sc);
+    debug_assert!(sc.is_satisfied_by(child.size()), "widget picked the size {:?}, which the constraints {sc:?} do not allow", child.size());
+}
+
+// the sizes that a widget is allowed to be
+// min is always finite, but max can be infinite along an axis to mean that there is no limit on that axis,
+// for example along the scrolling direction of a scroll view or when measuring how big a widget wants to be
+// widgets have to pick a finite size even when an axis is unbounded, usually by going by the size of their content,
+// which is also what widgets that fill the space they are given, like Expand, Center and Align, do along an unbounded axis
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub struct SizeConstraints {
     pub min: graphics::Vector2f,
     pub max: graphics::Vector2f,
 }
 impl SizeConstraints {
+    // exactly this size
+    pub fn tight(size: graphics::Vector2f) -> SizeConstraints {
+        SizeConstraints { min: size, max: size }
+    }
+    // any size up to max
+    pub fn loose(max: graphics::Vector2f) -> SizeConstraints {
+        SizeConstraints { min: graphics::Vector2f::new(0.0, 0.0), max }
+    }
+    // any size at all
+    pub fn unbounded() -> SizeConstraints {
+        SizeConstraints::loose(graphics::Vector2f::new(f32::INFINITY, f32::INFINITY))
+    }
+
+    // these constraints with no minimum or maximum along the direction given, for example for the content of a scroll view
+    pub fn unbounded_along(&self, direction: Direction) -> SizeConstraints {
+        let unbounded = SizeConstraints::unbounded();
+        SizeConstraints {
+            min: direction.make_vector_in_direction(direction.take_major_component(unbounded.min), direction.take_minor_component(self.min)),
+            max: direction.make_vector_in_direction(direction.take_major_component(unbounded.max), direction.take_minor_component(self.max)),
+        }
+    }
+    pub fn has_bounded_width(&self) -> bool {
+        self.max.x.is_finite()
+    }
+    pub fn has_bounded_height(&self) -> bool {
+        self.max.y.is_finite()
+    }
+
+    // whether there is any size that fits these constraints
+    pub fn is_valid(&self) -> bool {
+        let axis_is_valid = |min: f32, max: f32| min.is_finite() && min >= 0.0 && !max.is_nan() && max >= min;
+        axis_is_valid(self.min.x, self.max.x) && axis_is_valid(self.min.y, self.max.y)
+    }
+    // whether a widget is allowed to be this size, which has to be finite even on an unbounded axis
+    pub fn is_satisfied_by(&self, size: graphics::Vector2f) -> bool {
+        let axis_is_satisfied = |size: f32, min: f32, max: f32| size.is_finite() && size >= min && size <= max;
+        axis_is_satisfied(size.x, self.min.x, self.max.x) && axis_is_satisfied(size.y, self.min.y, self.max.y)
+    }
+
     pub fn with_no_min(&self) -> SizeConstraints {
-        SizeConstraints { min: graphics::Vector2f::new(0.0, 0.0), max: self.max }
+        SizeConstraints::loose(self.max)
     }
 
     pub fn clamp_size(&self, size: graphics::Vector2f) -> graphics::Vector2f {
@@ -21,3 +84,168 @@ impl SizeConstraints {
         }
     }
 }
+
+// an axis, used by widgets that lay things out along one axis and by the intrinsic size methods
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+impl Direction {
+    pub fn make_vector_in_direction<T>(&self, major_component: T, minor_component: T) -> graphics::Vector2<T> {
+        match self {
+            Direction::Horizontal => graphics::Vector2::new(major_component, minor_component),
+            Direction::Vertical => graphics::Vector2::new(minor_component, major_component),
+        }
+    }
+
+    pub fn take_major_component<T>(&self, v: graphics::Vector2<T>) -> T {
+        match self {
+            Direction::Horizontal => v.x,
+            Direction::Vertical => v.y,
+        }
+    }
+
+    pub fn take_minor_component<T>(&self, v: graphics::Vector2<T>) -> T {
+        match self {
+            Direction::Horizontal => v.y,
+            Direction::Vertical => v.x,
+        }
+    }
+
+    pub fn perpendicular(&self) -> Direction {
+        match self {
+            Direction::Horizontal => Direction::Vertical,
+            Direction::Vertical => Direction::Horizontal,
+        }
+    }
+}
+
+// remembers what a widget was last laid out with so that laying it out again can be skipped when nothing changed
+// widgets mark it dirty when something that their own layout depends on changes, and ask their children about the rest with ActualWidget::needs_layout
+// widgets that just pass their constraints on to their child and take its size, like Expand, Either, Tag, Focusable, KeymapContext,
+// RespondsToKeyboard and the widgets that fixed_size makes, have nothing to skip so they do not have one and are never counted by layout_count
+pub struct LayoutCache {
+    laid_out_with: Option<SizeConstraints>,
+    dirty: bool,
+}
+impl LayoutCache {
+    pub fn new() -> LayoutCache {
+        LayoutCache { laid_out_with: None, dirty: true }
+    }
+
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    // for update_actual_widget: replaces the old value with the new one, marking the cache dirty if they are different
+    pub fn update<T: PartialEq>(&mut self, old: &mut T, new: T) {
+        if *old != new {
+            *old = new;
+            self.dirty = true;
+        }
+    }
+    // like update but for functions, which cannot be compared
+    // a function without any state, like a plain fn or a closure that captures nothing, always does the same thing as the one it replaces,
+    // but anything else is treated as changed
+    pub fn update_fn<F>(&mut self, old: &mut F, new: F) {
+        *old = new;
+        if std::mem::size_of::<F>() != 0 {
+            self.dirty = true;
+        }
+    }
+
+    // layout can be skipped if this is true and the widget does not need layout for any other reason
+    pub fn was_laid_out_with(&self, sc: SizeConstraints) -> bool {
+        self.laid_out_with == Some(sc)
+    }
+    // called at the end of every layout that was not skipped
+    pub fn laid_out(&mut self, sc: SizeConstraints) {
+        self.laid_out_with = Some(sc);
+        self.dirty = false;
+        LAYOUT_COUNT.set(LAYOUT_COUNT.get() + 1);
+    }
+
+    // widgets whose layout depends on animated values use these instead of laid_out and is_dirty, with whether any of those values are animating
+    // the cache stays dirty while they are so that there is one more layout with the final values after the animation is over
+    pub fn laid_out_animating(&mut self, sc: SizeConstraints, animating: bool) {
+        self.laid_out(sc);
+        self.dirty = animating;
+    }
+    pub fn is_dirty_or_animating(&self, animating: bool) -> bool {
+        self.dirty || animating
+    }
+}
+impl Default for LayoutCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{layout_child, layout_count, Direction, SizeConstraints};
+    use crate::{
+        actual_widget::{ActualWidget, ActualWidgetIdMaker},
+        app, graphics,
+        widgets::{center::Center, expand::Expand, flex::ItemSettings, focusable::Focusable, label::Label, tag::Tag, test_rect::TestRect, Widget},
+        App, Settings,
+    };
+
+    struct Model {
+        text: String,
+    }
+
+    fn to_widget(model: &Model) -> impl Widget<Model> {
+        Center::new(Tag::new(
+            "flex",
+            crate::flex!(vertical {
+                changing: (ItemSettings::fixed(), Label::new(model.text.clone(), graphics::Fonts::text_font, 16)),
+                unchanging: (ItemSettings::fixed(), Focusable::new(Label::new("unchanging".to_string(), graphics::Fonts::text_font, 16))),
+            }),
+        ))
+    }
+
+    #[test]
+    fn only_changed_widgets_and_their_ancestors_are_laid_out() {
+        let mut app = App::new_headless(Settings::default(), graphics::Vector2f::new(200.0, 200.0), Model { text: "a".to_string() }, to_widget);
+
+        // nothing changed so nothing is laid out
+        let before = layout_count();
+        app.update();
+        assert_eq!(layout_count(), before);
+
+        // the changed label, the flex and the center, but not the other label or the tag and focusable which have no layout cache
+        app.model_mut().text = "b".to_string();
+        let before = layout_count();
+        app.update();
+        assert_eq!(layout_count() - before, 3);
+
+        let before = layout_count();
+        app.update();
+        assert_eq!(layout_count(), before);
+    }
+
+    // lays the widget out on its own and gives the size that it picked
+    fn laid_out_size(widget: impl Widget<()>, sc: SizeConstraints) -> graphics::Vector2f {
+        let mut actual_widget = widget.to_actual_widget(&mut ActualWidgetIdMaker::new());
+        layout_child(&mut actual_widget, &app::load_graphics_context(), sc);
+        actual_widget.size()
+    }
+
+    #[test]
+    fn unbounded_axes_go_by_the_size_of_the_content() {
+        let rect = || TestRect::new(graphics::Color::WHITE, graphics::Vector2f::new(30.0, 20.0));
+        assert_eq!(laid_out_size(Center::new(rect()), SizeConstraints::unbounded()), graphics::Vector2f::new(30.0, 20.0));
+        assert_eq!(laid_out_size(Expand::new(rect()), SizeConstraints::unbounded()), graphics::Vector2f::new(30.0, 20.0));
+
+        // the bounded axis is still filled
+        let sc = SizeConstraints::loose(graphics::Vector2f::new(100.0, 100.0)).unbounded_along(Direction::Vertical);
+        assert_eq!(laid_out_size(Center::new(rect()), sc), graphics::Vector2f::new(100.0, 20.0));
+        assert_eq!(laid_out_size(Expand::new(rect()), sc), graphics::Vector2f::new(100.0, 20.0));
+    }
+}
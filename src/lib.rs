@@ -1,8 +1,11 @@
+pub(crate) mod clock;
 pub(crate) mod lens;
 
 pub mod actual_widget;
+pub mod app;
 pub mod event;
 pub mod graphics; // TODO: i think this ideally should not be exported
+pub mod keymap;
 pub mod layout;
 
 #[macro_use]
@@ -10,4 +13,10 @@ pub mod widgets;
 
 pub(crate) mod mainloop;
 
-pub use crate::{graphics::Fonts, mainloop::run, widgets::Widget};
+pub use crate::{
+    app::{App, Settings},
+    graphics::Fonts,
+    keymap::Keymap,
+    mainloop::{run, run_with_settings},
+    widgets::Widget,
+};
@@ -1,18 +1,33 @@
+pub mod align;
+pub mod aspect_ratio;
 pub mod center;
 pub mod clickable;
+pub mod constraint_layout;
 pub mod either;
 pub mod empty;
 pub mod expand;
 #[macro_use]
 pub mod flex;
 pub mod fixed_size;
+pub mod focusable;
+pub mod fractionally_sized;
+pub mod grid;
+pub mod intrinsic;
+pub mod keymap_context;
 pub mod label;
+pub mod layout_builder;
 pub mod max_size;
 pub mod min_size;
 pub mod padding;
-pub(crate) mod responds_to_keyboard; // not finished yet so not exported
+pub mod responds_to_keyboard;
+pub mod scroll;
+pub mod split;
+pub mod stack;
+pub mod tag;
 pub(crate) mod test_rect;
+pub mod text_field;
 pub mod vsplit;
+pub mod wrap;
 
 use crate::actual_widget::{ActualWidget, ActualWidgetIdMaker};
 
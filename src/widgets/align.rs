@@ -0,0 +1,200 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use crate::{
+    actual_widget::{animated::Animated, ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
+    widgets::Widget,
+};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Alignment {
+    Start,
+    Center,
+    End,
+    // how far along the free space the child goes, from 0.0 (start) to 1.0 (end)
+    Fraction(f32),
+}
+
+impl Alignment {
+    fn fraction(self) -> f32 {
+        match self {
+            Alignment::Start => 0.0,
+            Alignment::Center => 0.5,
+            Alignment::End => 1.0,
+            Alignment::Fraction(fraction) => fraction,
+        }
+    }
+}
+
+pub struct Align<Data, Child: Widget<Data>> {
+    child: Child,
+    horizontal: Alignment,
+    vertical: Alignment,
+    shrink_wrap_width: bool,
+    shrink_wrap_height: bool,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+pub struct AlignActualWidget<Data, Child: ActualWidget<Data>> {
+    child: Child,
+    size: graphics::Vector2f,
+    horizontal: Animated<f32>,
+    vertical: Animated<f32>,
+    shrink_wrap_width: bool,
+    shrink_wrap_height: bool,
+    layout_cache: layout::LayoutCache,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+impl<Data, Child: Widget<Data>> Align<Data, Child> {
+    pub fn new(child: Child, horizontal: Alignment, vertical: Alignment) -> Self {
+        Self { child, horizontal, vertical, shrink_wrap_width: false, shrink_wrap_height: false, _phantom: PhantomData }
+    }
+
+    pub fn top_left(child: Child) -> Self {
+        Self::new(child, Alignment::Start, Alignment::Start)
+    }
+    pub fn top_center(child: Child) -> Self {
+        Self::new(child, Alignment::Center, Alignment::Start)
+    }
+    pub fn top_right(child: Child) -> Self {
+        Self::new(child, Alignment::End, Alignment::Start)
+    }
+    pub fn center_left(child: Child) -> Self {
+        Self::new(child, Alignment::Start, Alignment::Center)
+    }
+    pub fn center(child: Child) -> Self {
+        Self::new(child, Alignment::Center, Alignment::Center)
+    }
+    pub fn center_right(child: Child) -> Self {
+        Self::new(child, Alignment::End, Alignment::Center)
+    }
+    pub fn bottom_left(child: Child) -> Self {
+        Self::new(child, Alignment::Start, Alignment::End)
+    }
+    pub fn bottom_center(child: Child) -> Self {
+        Self::new(child, Alignment::Center, Alignment::End)
+    }
+    pub fn bottom_right(child: Child) -> Self {
+        Self::new(child, Alignment::End, Alignment::End)
+    }
+
+    // make this widget only as big as the child along the axes given instead of taking up all of the space available
+    pub fn with_shrink_wrap(mut self, width: bool, height: bool) -> Self {
+        self.shrink_wrap_width = width;
+        self.shrink_wrap_height = height;
+        self
+    }
+}
+
+impl<Data, Child: Widget<Data>> Widget<Data> for Align<Data, Child> {
+    type ActualWidget = AlignActualWidget<Data, <Child as Widget<Data>>::ActualWidget>;
+
+    fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        AlignActualWidget {
+            child: self.child.to_actual_widget(id_maker),
+            size: graphics::Vector2f::new(0.0, 0.0),
+            horizontal: Animated::new(self.horizontal.fraction()),
+            vertical: Animated::new(self.vertical.fraction()),
+            shrink_wrap_width: self.shrink_wrap_width,
+            shrink_wrap_height: self.shrink_wrap_height,
+            layout_cache: layout::LayoutCache::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        self.child.update_actual_widget(&mut actual_widget.child, id_maker);
+        actual_widget.horizontal.set(self.horizontal.fraction());
+        actual_widget.vertical.set(self.vertical.fraction());
+        actual_widget.layout_cache.update(&mut actual_widget.shrink_wrap_width, self.shrink_wrap_width);
+        actual_widget.layout_cache.update(&mut actual_widget.shrink_wrap_height, self.shrink_wrap_height);
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> AlignActualWidget<Data, Child> {
+    fn child_top_left(&self, top_left: graphics::Vector2f) -> graphics::Vector2f {
+        let free_space = self.size - self.child.size();
+        top_left + graphics::Vector2f::new(free_space.x * self.horizontal.get_lerped(), free_space.y * self.vertical.get_lerped())
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for AlignActualWidget<Data, Child> {
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        layout::layout_child(&mut self.child, graphics_context, sc.with_no_min());
+        let fill_width = !self.shrink_wrap_width && sc.max.x.is_finite();
+        let fill_height = !self.shrink_wrap_height && sc.max.y.is_finite();
+        self.size = sc.clamp_size(graphics::Vector2f::new(
+            if fill_width { sc.max.x } else { self.child.size().x },
+            if fill_height { sc.max.y } else { self.child.size().y },
+        ));
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.child.needs_layout()
+    }
+
+    fn draw(
+        &self,
+        graphics_context: &graphics::GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
+    ) {
+        self.child.draw(graphics_context, target, self.child_top_left(top_left), hover, focus);
+    }
+
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_> {
+        self.child.find_hover(self.child_top_left(top_left), mouse)
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        self.child.find_focusable()
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.child.find_path(target)
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.child.find_rect(self.child_top_left(top_left), query)
+    }
+
+    fn size(&self) -> graphics::Vector2f {
+        self.size
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.child.baseline().map(|baseline| self.child_top_left(graphics::Vector2f::new(0.0, 0.0)).y + baseline)
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.min_intrinsic_size(graphics_context, direction, cross_size)
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.max_intrinsic_size(graphics_context, direction, cross_size)
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        let child_top_left = self.child_top_left(top_left);
+        self.child.dispatch_event(child_top_left, data, target, event, ctx);
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::Event, _: &mut event::EventContext) {}
+}
@@ -0,0 +1,162 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use crate::{
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
+    widgets::Widget,
+};
+
+// makes the child as big as it can be while keeping its width divided by its height equal to the ratio
+pub struct AspectRatio<Data, Child: Widget<Data>> {
+    child: Child,
+    ratio: f32,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+pub struct AspectRatioActualWidget<Data, Child: ActualWidget<Data>> {
+    child: Child,
+    ratio: f32,
+    layout_cache: layout::LayoutCache,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+impl<Data, Child: Widget<Data>> AspectRatio<Data, Child> {
+    pub fn new(child: Child, ratio: f32) -> Self {
+        assert!(ratio.is_finite() && ratio > 0.0, "aspect ratio has to be positive and finite but is {ratio}");
+        Self { child, ratio, _phantom: PhantomData }
+    }
+}
+
+impl<Data, Child: Widget<Data>> Widget<Data> for AspectRatio<Data, Child> {
+    type ActualWidget = AspectRatioActualWidget<Data, <Child as Widget<Data>>::ActualWidget>;
+
+    fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        AspectRatioActualWidget {
+            child: self.child.to_actual_widget(id_maker),
+            ratio: self.ratio,
+            layout_cache: layout::LayoutCache::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        actual_widget.layout_cache.update(&mut actual_widget.ratio, self.ratio);
+        self.child.update_actual_widget(&mut actual_widget.child, id_maker);
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> AspectRatioActualWidget<Data, Child> {
+    // the size along the direction given that matches the ratio when the size along the other direction is cross_size
+    fn size_for(&self, direction: Direction, cross_size: f32) -> f32 {
+        match direction {
+            Direction::Horizontal => cross_size * self.ratio,
+            Direction::Vertical => cross_size / self.ratio,
+        }
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for AspectRatioActualWidget<Data, Child> {
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        // start as wide as possible and then shrink until the height fits
+        let mut width = if sc.max.x.is_finite() {
+            sc.max.x
+        } else if sc.max.y.is_finite() {
+            self.size_for(Direction::Horizontal, sc.max.y)
+        } else {
+            // there is no limit on either direction so go by how big the child wants to be
+            self.child.max_intrinsic_size(graphics_context, Direction::Horizontal, f32::INFINITY)
+        };
+        let mut height = self.size_for(Direction::Vertical, width);
+        if height > sc.max.y {
+            height = sc.max.y;
+            width = self.size_for(Direction::Horizontal, height);
+        }
+        if width < sc.min.x {
+            width = sc.min.x;
+            height = self.size_for(Direction::Vertical, width);
+        }
+        if height < sc.min.y {
+            height = sc.min.y;
+            width = self.size_for(Direction::Horizontal, height);
+        }
+
+        // if the constraints do not allow the ratio at all then the constraints win
+        let size = sc.clamp_size(graphics::Vector2f::new(width, height));
+        layout::layout_child(&mut self.child, graphics_context, layout::SizeConstraints::tight(size));
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.child.needs_layout()
+    }
+
+    fn draw(
+        &self,
+        graphics_context: &graphics::GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
+    ) {
+        self.child.draw(graphics_context, target, top_left, hover, focus);
+    }
+
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_> {
+        self.child.find_hover(top_left, mouse)
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        self.child.find_focusable()
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.child.find_path(target)
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.child.find_rect(top_left, query)
+    }
+
+    fn size(&self) -> graphics::Vector2f {
+        self.child.size()
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.child.baseline()
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        if cross_size.is_finite() {
+            self.size_for(direction, cross_size)
+        } else {
+            self.child.min_intrinsic_size(graphics_context, direction, cross_size)
+        }
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        if cross_size.is_finite() {
+            self.size_for(direction, cross_size)
+        } else {
+            self.child.max_intrinsic_size(graphics_context, direction, cross_size)
+        }
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        self.child.dispatch_event(top_left, data, target, event, ctx);
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::Event, _: &mut event::EventContext) {}
+}
@@ -1,8 +1,9 @@
 use std::{collections::HashSet, marker::PhantomData};
 
 use crate::{
-    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker},
-    event, graphics, layout,
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
     widgets::Widget,
 };
 
@@ -15,6 +16,7 @@ pub struct Center<Data, Child: Widget<Data>> {
 pub struct CenterActualWidget<Data, Child: ActualWidget<Data>> {
     child: Child,
     size: graphics::Vector2f,
+    layout_cache: layout::LayoutCache,
 
     _phantom: PhantomData<fn(&mut Data)>,
 }
@@ -29,7 +31,12 @@ impl<Data, Child: Widget<Data>> Widget<Data> for Center<Data, Child> {
     type ActualWidget = CenterActualWidget<Data, <Child as Widget<Data>>::ActualWidget>;
 
     fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
-        CenterActualWidget { child: self.child.to_actual_widget(id_maker), size: graphics::Vector2f::new(0.0, 0.0), _phantom: PhantomData }
+        CenterActualWidget {
+            child: self.child.to_actual_widget(id_maker),
+            size: graphics::Vector2f::new(0.0, 0.0),
+            layout_cache: layout::LayoutCache::new(),
+            _phantom: PhantomData,
+        }
     }
 
     fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
@@ -39,8 +46,19 @@ impl<Data, Child: Widget<Data>> Widget<Data> for Center<Data, Child> {
 
 impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for CenterActualWidget<Data, Child> {
     fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
-        self.child.layout(graphics_context, sc.with_no_min());
-        self.size = sc.max;
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        layout::layout_child(&mut self.child, graphics_context, sc.with_no_min());
+        self.size = sc.clamp_size(graphics::Vector2f::new(
+            if sc.has_bounded_width() { sc.max.x } else { self.child.size().x },
+            if sc.has_bounded_height() { sc.max.y } else { self.child.size().y },
+        ));
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.child.needs_layout()
     }
 
     fn draw(
@@ -49,26 +67,55 @@ impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for CenterActualWidget<
         target: &mut dyn graphics::RenderTarget,
         top_left: graphics::Vector2f,
         hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
     ) {
-        self.child.draw(graphics_context, target, center(top_left, self.size, self.child.size()), hover);
+        self.child.draw(graphics_context, target, center(top_left, self.size, self.child.size()), hover, focus);
     }
 
     fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<(dyn Iterator<Item = (ActualWidgetId, bool)> + '_)> {
         self.child.find_hover(center(top_left, self.size, self.child.size()), mouse)
     }
 
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        self.child.find_focusable()
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.child.find_path(target)
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.child.find_rect(center(top_left, self.size, self.child.size()), query)
+    }
+
     fn size(&self) -> graphics::Vector2f {
         self.size
     }
 
-    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: event::TargetedEvent) {
-        self.child.dispatch_event(center(top_left, self.size, self.child.size()), data, target, event);
+    fn baseline(&self) -> Option<f32> {
+        self.child.baseline().map(|baseline| center(graphics::Vector2f::new(0.0, 0.0), self.size, self.child.size()).y + baseline)
     }
 
-    fn targeted_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::TargetedEvent) {}
-    fn general_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, event: event::GeneralEvent) {
-        self.child.general_event(top_left, data, event)
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.min_intrinsic_size(graphics_context, direction, cross_size)
     }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.max_intrinsic_size(graphics_context, direction, cross_size)
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        self.child.dispatch_event(center(top_left, self.size, self.child.size()), data, target, event, ctx);
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::Event, _: &mut event::EventContext) {}
 }
 
 fn center(top_left: graphics::Vector2f, max_size: graphics::Vector2f, child_size: graphics::Vector2f) -> graphics::Vector2f {
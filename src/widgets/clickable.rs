@@ -1,8 +1,9 @@
 use std::{collections::HashSet, marker::PhantomData};
 
 use crate::{
-    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker},
-    event, graphics, layout,
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
     widgets::Widget,
 };
 
@@ -10,9 +11,30 @@ use crate::{
 pub enum MouseButton {
     Left,
     Right,
+    Middle,
+    XButton1,
+    XButton2,
+    Any,
 }
+
+impl MouseButton {
+    fn matches(self, button: sfml::window::mouse::Button) -> bool {
+        use sfml::window::mouse::Button;
+        matches!(
+            (self, button),
+            (MouseButton::Left, Button::Left)
+                | (MouseButton::Right, Button::Right)
+                | (MouseButton::Middle, Button::Middle)
+                | (MouseButton::XButton1, Button::XButton1)
+                | (MouseButton::XButton2, Button::XButton2)
+                | (MouseButton::Any, _)
+        )
+    }
+}
+
 pub struct Clickable<Data, NormalChild: Widget<Data>, ChildOnClicked: Widget<Data>, Callback: Fn(&mut Data)> {
     mouse_button: MouseButton,
+    click_count: u32,
     on_click: Callback,
     normal_child: NormalChild,
     child_on_clicked: ChildOnClicked,
@@ -23,19 +45,38 @@ pub struct Clickable<Data, NormalChild: Widget<Data>, ChildOnClicked: Widget<Dat
 pub struct ClickableActualWidget<Data, NormalChild: ActualWidget<Data>, ChildOnClicked: ActualWidget<Data>, Callback: Fn(&mut Data)> {
     id: ActualWidgetId,
     mouse_button: MouseButton,
+    click_count: u32,
     on_click: Callback,
     normal_child: NormalChild,
     child_on_clicked: ChildOnClicked,
 
+    // the button that is being held down on this widget and the click count of the press
+    press: Option<(sfml::window::mouse::Button, u32)>,
+    // false if the mouse was dragged off of the widget while pressing it
     clicked: bool,
+    // the two children can be different sizes, so the parent has to lay this out again when clicked changes
+    layout_cache: layout::LayoutCache,
 
     _phantom: PhantomData<fn(&mut Data)>,
     _private: (),
 }
 
 impl<Data, NormalChild: Widget<Data>, ChildOnClicked: Widget<Data>, Callback: Fn(&mut Data)> Clickable<Data, NormalChild, ChildOnClicked, Callback> {
-    pub(crate) fn new(mouse_button: MouseButton, on_click: Callback, normal_child: NormalChild, child_on_clicked: ChildOnClicked) -> Self {
-        Self { mouse_button, on_click, normal_child, child_on_clicked, _phantom: PhantomData }
+    pub fn new(mouse_button: MouseButton, on_click: Callback, normal_child: NormalChild, child_on_clicked: ChildOnClicked) -> Self {
+        Self::new_with_click_count(mouse_button, 1, on_click, normal_child, child_on_clicked)
+    }
+    pub fn new_double_click(mouse_button: MouseButton, on_click: Callback, normal_child: NormalChild, child_on_clicked: ChildOnClicked) -> Self {
+        Self::new_with_click_count(mouse_button, 2, on_click, normal_child, child_on_clicked)
+    }
+    // on_click is only called for clicks that are at least the click_count-th click in a row
+    pub fn new_with_click_count(
+        mouse_button: MouseButton,
+        click_count: u32,
+        on_click: Callback,
+        normal_child: NormalChild,
+        child_on_clicked: ChildOnClicked,
+    ) -> Self {
+        Self { mouse_button, click_count, on_click, normal_child, child_on_clicked, _phantom: PhantomData }
     }
 }
 
@@ -49,10 +90,13 @@ impl<Data, NormalChild: Widget<Data>, ChildOnClicked: Widget<Data>, Callback: Fn
         ClickableActualWidget {
             id: id_maker.next_id(),
             mouse_button: self.mouse_button,
+            click_count: self.click_count,
             on_click: self.on_click,
             normal_child: self.normal_child.to_actual_widget(id_maker),
             child_on_clicked: self.child_on_clicked.to_actual_widget(id_maker),
+            press: None,
             clicked: false,
+            layout_cache: layout::LayoutCache::new(),
             _phantom: PhantomData,
             _private: (),
         }
@@ -60,6 +104,7 @@ impl<Data, NormalChild: Widget<Data>, ChildOnClicked: Widget<Data>, Callback: Fn
 
     fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
         actual_widget.mouse_button = self.mouse_button;
+        actual_widget.click_count = self.click_count;
         actual_widget.on_click = self.on_click;
         self.normal_child.update_actual_widget(&mut actual_widget.normal_child, id_maker);
         self.child_on_clicked.update_actual_widget(&mut actual_widget.child_on_clicked, id_maker);
@@ -70,8 +115,16 @@ impl<Data, NormalChild: ActualWidget<Data>, ChildOnClicked: ActualWidget<Data>,
     for ClickableActualWidget<Data, NormalChild, ChildOnClicked, Callback>
 {
     fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
-        self.normal_child.layout(graphics_context, sc);
-        self.child_on_clicked.layout(graphics_context, sc);
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        layout::layout_child(&mut self.normal_child, graphics_context, sc);
+        layout::layout_child(&mut self.child_on_clicked, graphics_context, sc);
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.normal_child.needs_layout() || self.child_on_clicked.needs_layout()
     }
 
     fn draw(
@@ -80,11 +133,12 @@ impl<Data, NormalChild: ActualWidget<Data>, ChildOnClicked: ActualWidget<Data>,
         target: &mut dyn graphics::RenderTarget,
         top_left: graphics::Vector2f,
         hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
     ) {
         if self.clicked {
-            self.child_on_clicked.draw(graphics_context, target, top_left, hover);
+            self.child_on_clicked.draw(graphics_context, target, top_left, hover, focus);
         } else {
-            self.normal_child.draw(graphics_context, target, top_left, hover);
+            self.normal_child.draw(graphics_context, target, top_left, hover, focus);
         }
     }
 
@@ -96,6 +150,33 @@ impl<Data, NormalChild: ActualWidget<Data>, ChildOnClicked: ActualWidget<Data>,
         )
     }
 
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        if self.clicked {
+            self.child_on_clicked.find_focusable()
+        } else {
+            self.normal_child.find_focusable()
+        }
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        if target == self.id {
+            Some(vec![self.id])
+        } else {
+            if self.clicked { self.child_on_clicked.find_path(target) } else { self.normal_child.find_path(target) }
+                .map(|path| std::iter::once(self.id).chain(path).collect())
+        }
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        if query.matches_id(self.id) {
+            Some(graphics::FloatRect::from_vecs(top_left, self.size()))
+        } else if self.clicked {
+            self.child_on_clicked.find_rect(top_left, query)
+        } else {
+            self.normal_child.find_rect(top_left, query)
+        }
+    }
+
     fn size(&self) -> graphics::Vector2f {
         if self.clicked {
             self.child_on_clicked.size()
@@ -104,58 +185,94 @@ impl<Data, NormalChild: ActualWidget<Data>, ChildOnClicked: ActualWidget<Data>,
         }
     }
 
-    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: event::TargetedEvent) {
+    fn baseline(&self) -> Option<f32> {
+        if self.clicked {
+            self.child_on_clicked.baseline()
+        } else {
+            self.normal_child.baseline()
+        }
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.normal_child.min_intrinsic_size(graphics_context, direction, cross_size).max(self.child_on_clicked.min_intrinsic_size(
+            graphics_context,
+            direction,
+            cross_size,
+        ))
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.normal_child.max_intrinsic_size(graphics_context, direction, cross_size).max(self.child_on_clicked.max_intrinsic_size(
+            graphics_context,
+            direction,
+            cross_size,
+        ))
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
         if target == self.id {
-            self.targeted_event(top_left, data, event);
+            self.handle_event(top_left, data, event, ctx);
         }
 
         if self.clicked {
-            self.child_on_clicked.dispatch_event(top_left, data, target, event);
+            self.child_on_clicked.dispatch_event(top_left, data, target, event, ctx);
         } else {
-            self.normal_child.dispatch_event(top_left, data, target, event);
+            self.normal_child.dispatch_event(top_left, data, target, event, ctx);
         }
     }
 
-    fn targeted_event(&mut self, _: graphics::Vector2f, _: &mut Data, event: event::TargetedEvent) {
-        match event {
-            event::TargetedEvent::LeftMouseDown(_) => {
-                if self.mouse_button == MouseButton::Left {
-                    self.clicked = true;
-                }
-            }
-            event::TargetedEvent::RightMouseDown(_) => {
-                if self.mouse_button == MouseButton::Right {
-                    self.clicked = true;
-                }
-            }
+    fn handle_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, event: event::Event, ctx: &mut event::EventContext) {
+        if ctx.phase() == event::Phase::Capture {
+            return;
         }
-    }
-    fn general_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, event: event::GeneralEvent) {
+
         match event {
-            event::GeneralEvent::MouseMoved(new_mouse_pos) => {
-                if !graphics::FloatRect::from_vecs(top_left, self.size()).contains(new_mouse_pos) {
-                    self.clicked = false;
+            event::Event::MouseDown { button, position: _, modifiers: _, click_count } => {
+                // pressing the same button again means the release of the last press never got here, so that press is started over
+                if self.press.is_none_or(|(pressed_button, _)| pressed_button == button) && self.mouse_button.matches(button) {
+                    self.press = Some((button, click_count));
+                    self.layout_cache.update(&mut self.clicked, true);
+                    ctx.capture_pointer();
+                    ctx.set_handled();
                 }
             }
-            event::GeneralEvent::LeftMouseUp => {
-                if self.mouse_button == MouseButton::Left && self.clicked {
-                    self.clicked = false;
-                    (self.on_click)(data);
+            event::Event::MouseMoved(new_mouse_pos) => {
+                ctx.request_cursor(event::CursorShape::Hand);
+                // only show as clicked if the mouse is still over this widget (releasing the mouse outside of the widget cancels the click)
+                if ctx.drag().is_some() && ctx.target() == self.id {
+                    let clicked = graphics::FloatRect::from_vecs(top_left, self.size()).contains(new_mouse_pos);
+                    self.layout_cache.update(&mut self.clicked, clicked);
+                    ctx.set_handled();
                 }
             }
-            event::GeneralEvent::RightMouseUp => {
-                if self.mouse_button == MouseButton::Right && self.clicked {
-                    self.clicked = false;
-                    (self.on_click)(data);
+            event::Event::MouseUp { button, .. } => {
+                if let Some((_, click_count)) = self.press.filter(|(pressed_button, _)| *pressed_button == button) {
+                    // the press ends even if the pointer was never captured (the app can refuse to capture it, or drop the capture when the tree changes),
+                    // but only a release that went to this widget as the captured target counts as a click
+                    let captured = ctx.drag().is_some() && ctx.target() == self.id;
+                    if captured && self.clicked && click_count >= self.click_count {
+                        (self.on_click)(data);
+                    }
+                    self.press = None;
+                    self.layout_cache.update(&mut self.clicked, false);
+                    if captured {
+                        ctx.set_handled();
+                    }
                 }
             }
-            event::GeneralEvent::KeyPressed { .. } => {}
-        }
-
-        if self.clicked {
-            self.child_on_clicked.general_event(top_left, data, event);
-        } else {
-            self.normal_child.general_event(top_left, data, event);
+            event::Event::KeyPressed { .. } => {}
+            event::Event::KeyReleased { .. } => {}
+            event::Event::TextEntered(_) => {}
+            event::Event::MouseWheelScrolled { .. } => {}
+            event::Event::PointerEnter => {}
+            event::Event::PointerLeave => {}
         }
     }
 }
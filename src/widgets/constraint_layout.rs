@@ -0,0 +1,500 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    marker::PhantomData,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use crate::{
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
+    widgets::Widget,
+};
+
+mod solver;
+
+// how much children want to keep the size they would like to be, which is weaker than any constraint given by the user
+const NATURAL_SIZE_WEIGHT: f64 = 0.1;
+// how much the container wants to be as small as it is allowed to be
+const SHRINK_WEIGHT: f64 = 0.01;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Edge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    CenterX,
+    CenterY,
+    Width,
+    Height,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Anchor {
+    Parent(Edge),
+    Child(usize, Edge),
+}
+
+// a sum of edges multiplied by numbers plus a constant, made with parent() and child() and the arithmetic operators
+#[derive(Clone, PartialEq, Debug)]
+pub struct Expression {
+    terms: Vec<(Anchor, f32)>,
+    constant: f32,
+}
+
+// an edge of the container itself, where the left and top edges are always at 0
+pub fn parent(edge: Edge) -> Expression {
+    Expression { terms: vec![(Anchor::Parent(edge), 1.0)], constant: 0.0 }
+}
+
+// an edge of the child at the index given
+pub fn child(index: usize, edge: Edge) -> Expression {
+    Expression { terms: vec![(Anchor::Child(index, edge), 1.0)], constant: 0.0 }
+}
+
+impl Expression {
+    pub fn constant(constant: f32) -> Self {
+        Self { terms: Vec::new(), constant }
+    }
+
+    pub fn equal_to(self, other: impl Into<Expression>) -> Constraint {
+        Constraint::new(self - other.into(), Relation::Equal)
+    }
+    pub fn at_most(self, other: impl Into<Expression>) -> Constraint {
+        Constraint::new(self - other.into(), Relation::LessOrEqual)
+    }
+    pub fn at_least(self, other: impl Into<Expression>) -> Constraint {
+        Constraint::new(self - other.into(), Relation::GreaterOrEqual)
+    }
+}
+
+impl From<f32> for Expression {
+    fn from(constant: f32) -> Self {
+        Expression::constant(constant)
+    }
+}
+
+impl<Other: Into<Expression>> Add<Other> for Expression {
+    type Output = Expression;
+
+    fn add(mut self, other: Other) -> Expression {
+        let other = other.into();
+        self.terms.extend(other.terms);
+        self.constant += other.constant;
+        self
+    }
+}
+
+impl<Other: Into<Expression>> Sub<Other> for Expression {
+    type Output = Expression;
+
+    fn sub(self, other: Other) -> Expression {
+        self + -other.into()
+    }
+}
+
+impl Mul<f32> for Expression {
+    type Output = Expression;
+
+    fn mul(self, amount: f32) -> Expression {
+        Expression {
+            terms: self.terms.into_iter().map(|(anchor, coefficient)| (anchor, coefficient * amount)).collect(),
+            constant: self.constant * amount,
+        }
+    }
+}
+
+impl Neg for Expression {
+    type Output = Expression;
+
+    fn neg(self) -> Expression {
+        self * -1.0
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Relation {
+    Equal,
+    LessOrEqual,
+    GreaterOrEqual,
+}
+
+// required constraints always hold, and when the other constraints conflict the stronger ones win
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Strength {
+    #[default]
+    Required,
+    Strong,
+    Medium,
+    Weak,
+}
+
+impl Strength {
+    // the weight of the constraint in the solver's objective, or None if it is required
+    fn weight(self) -> Option<f64> {
+        match self {
+            Strength::Required => None,
+            Strength::Strong => Some(1.0e6),
+            Strength::Medium => Some(1.0e3),
+            Strength::Weak => Some(1.0),
+        }
+    }
+}
+
+// `expression (relation) 0`, made with Expression::equal_to, Expression::at_most and Expression::at_least
+#[derive(Clone, PartialEq, Debug)]
+pub struct Constraint {
+    expression: Expression,
+    relation: Relation,
+    strength: Strength,
+}
+
+impl Constraint {
+    fn new(expression: Expression, relation: Relation) -> Self {
+        Self { expression, relation, strength: Strength::default() }
+    }
+
+    pub fn with_strength(self, strength: Strength) -> Self {
+        Self { strength, ..self }
+    }
+}
+
+// a problem with the constraints that was found during layout
+// the constraint at the index given is left out and the rest of the constraints are still solved
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Diagnostic {
+    // the constraint is required but conflicts with the required constraints before it or with the size the container has to be
+    Unsatisfiable { constraint: usize },
+    // the constraint mentions a child that does not exist
+    MissingChild { constraint: usize, child: usize },
+    // the constraint has a number in it that is infinite or NaN
+    NotFinite { constraint: usize },
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::Unsatisfiable { constraint } => {
+                write!(f, "constraint {constraint} cannot be satisfied together with the required constraints")
+            }
+            Diagnostic::MissingChild { constraint, child } => write!(f, "constraint {constraint} refers to child {child} which does not exist"),
+            Diagnostic::NotFinite { constraint } => write!(f, "constraint {constraint} has a number in it that is not finite"),
+        }
+    }
+}
+
+type OnDiagnostics = Box<dyn Fn(&[Diagnostic])>;
+
+// positions and sizes children by solving linear equalities and inequalities between their edges and the container's edges
+// each child would like to be the size it wants to be and the container would like to be as small as it can be,
+// but both of those are weaker than any constraint that is given
+pub struct ConstraintLayout<Data, Child: Widget<Data>> {
+    children: Vec<Child>,
+    constraints: Vec<Constraint>,
+    on_diagnostics: Option<OnDiagnostics>,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+pub struct ConstraintLayoutActualWidget<Data, Child: ActualWidget<Data>> {
+    children: Vec<(graphics::Vector2f, Child)>,
+    constraints: Vec<Constraint>,
+
+    own_size: graphics::Vector2f,
+    // from the last layout
+    diagnostics: Vec<Diagnostic>,
+    on_diagnostics: Option<OnDiagnostics>,
+    layout_cache: layout::LayoutCache,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+    _private: (),
+}
+
+impl<Data, Child: Widget<Data>> ConstraintLayout<Data, Child> {
+    pub fn new(children: Vec<Child>, constraints: Vec<Constraint>) -> Self {
+        Self { children, constraints, on_diagnostics: None, _phantom: PhantomData }
+    }
+
+    // called with all of the problems that were found in a layout whenever they are different from the ones found in the layout before
+    // without this the problems are only logged, and only in debug builds
+    pub fn with_on_diagnostics(mut self, on_diagnostics: impl Fn(&[Diagnostic]) + 'static) -> Self {
+        self.on_diagnostics = Some(Box::new(on_diagnostics));
+        self
+    }
+}
+
+impl<Data, Child: Widget<Data>> Widget<Data> for ConstraintLayout<Data, Child> {
+    type ActualWidget = ConstraintLayoutActualWidget<Data, <Child as Widget<Data>>::ActualWidget>;
+
+    fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        ConstraintLayoutActualWidget {
+            children: self.children.into_iter().map(|child| (graphics::Vector2f::new(0.0, 0.0), child.to_actual_widget(id_maker))).collect(),
+            constraints: self.constraints,
+            own_size: graphics::Vector2f::new(0.0, 0.0),
+            diagnostics: Vec::new(),
+            on_diagnostics: self.on_diagnostics,
+            layout_cache: layout::LayoutCache::new(),
+            _phantom: PhantomData,
+            _private: (),
+        }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        let cache = &mut actual_widget.layout_cache;
+        cache.update(&mut actual_widget.constraints, self.constraints);
+        actual_widget.on_diagnostics = self.on_diagnostics;
+        if self.children.len() != actual_widget.children.len() {
+            cache.mark_dirty();
+        }
+
+        let ro_children = std::mem::take(&mut actual_widget.children);
+        let ro_children_infinite = ro_children.into_iter().map(Some).chain(std::iter::repeat_with(|| None));
+
+        let new_ro_children = self
+            .children
+            .into_iter()
+            .zip(ro_children_infinite)
+            .map(|(widget, ro)| match ro {
+                Some((offset, mut ro)) => {
+                    widget.update_actual_widget(&mut ro, id_maker);
+                    (offset, ro)
+                }
+                None => (graphics::Vector2f::new(0.0, 0.0), widget.to_actual_widget(id_maker)),
+            })
+            .collect();
+
+        actual_widget.children = new_ro_children;
+    }
+}
+
+// the rectangles that the solver found
+struct Solution {
+    own_size: graphics::Vector2f,
+    // (top left, size)
+    children: Vec<(graphics::Vector2f, graphics::Vector2f)>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+// the solver's variables are numbered with the container's width and height first and then the left, top, width and height of every child
+const PARENT_WIDTH: usize = 0;
+const PARENT_HEIGHT: usize = 1;
+fn child_variable(index: usize, offset: usize) -> usize {
+    2 + index * 4 + offset
+}
+const LEFT: usize = 0;
+const TOP: usize = 1;
+const WIDTH: usize = 2;
+const HEIGHT: usize = 3;
+
+// an edge as (variable, coefficient) terms, which are empty for the left and top edges of the container because those are always 0
+fn edge_terms(anchor: Anchor) -> Vec<(usize, f64)> {
+    let (start, size) = match anchor {
+        Anchor::Parent(Edge::Left | Edge::Right | Edge::CenterX | Edge::Width) => (None, PARENT_WIDTH),
+        Anchor::Parent(Edge::Top | Edge::Bottom | Edge::CenterY | Edge::Height) => (None, PARENT_HEIGHT),
+        Anchor::Child(index, Edge::Left | Edge::Right | Edge::CenterX | Edge::Width) => {
+            (Some(child_variable(index, LEFT)), child_variable(index, WIDTH))
+        }
+        Anchor::Child(index, Edge::Top | Edge::Bottom | Edge::CenterY | Edge::Height) => {
+            (Some(child_variable(index, TOP)), child_variable(index, HEIGHT))
+        }
+    };
+    let edge = match anchor {
+        Anchor::Parent(edge) | Anchor::Child(_, edge) => edge,
+    };
+    let (start_coefficient, size_coefficient) = match edge {
+        Edge::Left | Edge::Top => (1.0, 0.0),
+        Edge::Right | Edge::Bottom => (1.0, 1.0),
+        Edge::CenterX | Edge::CenterY => (1.0, 0.5),
+        Edge::Width | Edge::Height => (0.0, 1.0),
+    };
+    start
+        .map(|start| (start, start_coefficient))
+        .into_iter()
+        .chain(std::iter::once((size, size_coefficient)))
+        .filter(|(_, coefficient)| *coefficient != 0.0)
+        .collect()
+}
+
+impl<Data, Child: ActualWidget<Data>> ConstraintLayoutActualWidget<Data, Child> {
+    // natural_sizes are the sizes that the children would like to be
+    fn solve(&self, sc: layout::SizeConstraints, natural_sizes: &[graphics::Vector2f]) -> Solution {
+        let mut solver = solver::Solver::new();
+        let mut diagnostics = Vec::new();
+
+        // none of these can fail because nothing else has been added yet and the constraints have no conflicts between them
+        let mut add_internal = |terms: &[(usize, f64)], constant: f32, relation: Relation, weight: Option<f64>| {
+            let _ = solver.add_constraint(terms, -constant as f64, relation, weight);
+        };
+        for (variable, min, max) in [(PARENT_WIDTH, sc.min.x, sc.max.x), (PARENT_HEIGHT, sc.min.y, sc.max.y)] {
+            add_internal(&[(variable, 1.0)], min, Relation::GreaterOrEqual, None);
+            if max.is_finite() {
+                add_internal(&[(variable, 1.0)], max, Relation::LessOrEqual, None);
+            }
+        }
+        for index in 0..self.children.len() {
+            add_internal(&[(child_variable(index, WIDTH), 1.0)], 0.0, Relation::GreaterOrEqual, None);
+            add_internal(&[(child_variable(index, HEIGHT), 1.0)], 0.0, Relation::GreaterOrEqual, None);
+        }
+
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            if let Some(child) = constraint.expression.terms.iter().find_map(|(anchor, _)| match anchor {
+                Anchor::Child(child, _) if *child >= self.children.len() => Some(*child),
+                _ => None,
+            }) {
+                diagnostics.push(Diagnostic::MissingChild { constraint: index, child });
+                continue;
+            }
+            if !constraint.expression.constant.is_finite() || constraint.expression.terms.iter().any(|(_, coefficient)| !coefficient.is_finite()) {
+                diagnostics.push(Diagnostic::NotFinite { constraint: index });
+                continue;
+            }
+
+            let terms: Vec<(usize, f64)> = constraint
+                .expression
+                .terms
+                .iter()
+                .flat_map(|(anchor, coefficient)| {
+                    edge_terms(*anchor).into_iter().map(move |(variable, edge_coefficient)| (variable, edge_coefficient * *coefficient as f64))
+                })
+                .collect();
+            if solver.add_constraint(&terms, constraint.expression.constant as f64, constraint.relation, constraint.strength.weight()).is_err() {
+                diagnostics.push(Diagnostic::Unsatisfiable { constraint: index });
+            }
+        }
+
+        // these are weaker than everything else so they can only fail if the solver is broken, in which case they are just left out
+        for (index, natural_size) in natural_sizes.iter().enumerate() {
+            let _ = solver.add_constraint(&[(child_variable(index, WIDTH), 1.0)], -natural_size.x as f64, Relation::Equal, Some(NATURAL_SIZE_WEIGHT));
+            let _ =
+                solver.add_constraint(&[(child_variable(index, HEIGHT), 1.0)], -natural_size.y as f64, Relation::Equal, Some(NATURAL_SIZE_WEIGHT));
+        }
+        let _ = solver.add_constraint(&[(PARENT_WIDTH, 1.0)], -sc.min.x as f64, Relation::Equal, Some(SHRINK_WEIGHT));
+        let _ = solver.add_constraint(&[(PARENT_HEIGHT, 1.0)], -sc.min.y as f64, Relation::Equal, Some(SHRINK_WEIGHT));
+
+        let value = |variable: usize| solver.value(variable) as f32;
+        Solution {
+            own_size: sc.clamp_size(graphics::Vector2f::new(value(PARENT_WIDTH), value(PARENT_HEIGHT))),
+            children: (0..self.children.len())
+                .map(|index| {
+                    (
+                        graphics::Vector2f::new(value(child_variable(index, LEFT)), value(child_variable(index, TOP))),
+                        graphics::Vector2f::new(value(child_variable(index, WIDTH)).max(0.0), value(child_variable(index, HEIGHT)).max(0.0)),
+                    )
+                })
+                .collect(),
+            diagnostics,
+        }
+    }
+
+    fn natural_sizes(&self, measure: impl Fn(&Child, Direction, f32) -> f32) -> Vec<graphics::Vector2f> {
+        self.children
+            .iter()
+            .map(|(_, child)| {
+                let width = measure(child, Direction::Horizontal, f32::INFINITY);
+                graphics::Vector2f::new(width, measure(child, Direction::Vertical, width))
+            })
+            .collect()
+    }
+
+    fn intrinsic_size(&self, direction: Direction, cross_size: f32, measure: impl Fn(&Child, Direction, f32) -> f32) -> f32 {
+        let sc = layout::SizeConstraints::loose(direction.make_vector_in_direction(0.0, cross_size)).unbounded_along(direction);
+        direction.take_major_component(self.solve(sc, &self.natural_sizes(measure)).own_size)
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for ConstraintLayoutActualWidget<Data, Child> {
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        let natural_sizes = self.natural_sizes(|child, direction, cross_size| child.max_intrinsic_size(graphics_context, direction, cross_size));
+        let solution = self.solve(sc, &natural_sizes);
+
+        for ((offset, child), (top_left, size)) in self.children.iter_mut().zip(solution.children) {
+            layout::layout_child(child, graphics_context, layout::SizeConstraints::tight(size));
+            *offset = top_left;
+        }
+        self.own_size = solution.own_size;
+
+        // a child that keeps changing makes this get laid out every frame, so only report problems when they change
+        if solution.diagnostics != self.diagnostics {
+            match &self.on_diagnostics {
+                Some(on_diagnostics) => on_diagnostics(&solution.diagnostics),
+                None if cfg!(debug_assertions) => {
+                    for diagnostic in &solution.diagnostics {
+                        eprintln!("warning: constraint layout: {diagnostic}");
+                    }
+                }
+                None => {}
+            }
+            self.diagnostics = solution.diagnostics;
+        }
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.children.iter().any(|(_, child)| child.needs_layout())
+    }
+
+    fn draw(
+        &self,
+        graphics_context: &graphics::GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
+    ) {
+        for (offset, child) in &self.children {
+            child.draw(graphics_context, target, top_left + *offset, hover, focus);
+        }
+    }
+
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_> {
+        Box::new(self.children.iter().flat_map(move |(offset, child)| child.find_hover(top_left + *offset, mouse)))
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        Box::new(self.children.iter().flat_map(|(_, child)| child.find_focusable()))
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.children.iter().find_map(|(_, child)| child.find_path(target))
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.children.iter().find_map(|(offset, child)| child.find_rect(top_left + *offset, query))
+    }
+
+    fn size(&self) -> graphics::Vector2f {
+        self.own_size
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.children.iter().find_map(|(offset, child)| child.baseline().map(|baseline| offset.y + baseline))
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.intrinsic_size(direction, cross_size, |child, direction, cross_size| child.min_intrinsic_size(graphics_context, direction, cross_size))
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.intrinsic_size(direction, cross_size, |child, direction, cross_size| child.max_intrinsic_size(graphics_context, direction, cross_size))
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        for (offset, child) in &mut self.children {
+            child.dispatch_event(top_left + *offset, data, target, event, ctx);
+        }
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::Event, _: &mut event::EventContext) {}
+}
@@ -0,0 +1,413 @@
+// a small version of the cassowary simplex solver, based on the way kiwi implements it
+// every constraint is turned into a row of the tableau as it is added, and non-required constraints get error variables
+// that are added to the objective with their strength as the weight, so minimizing the objective satisfies the strongest constraints first
+// the tableau is rebuilt on every layout, so there is no support for removing constraints or for edit variables
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::Relation;
+
+const EPSILON: f64 = 1.0e-8;
+
+fn near_zero(value: f64) -> bool {
+    value.abs() < EPSILON
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SolverError {
+    // the constraint conflicts with the required constraints that were already added
+    Unsatisfiable,
+    // the objective can get infinitely small, which should not happen because all of the error variables are positive
+    Unbounded,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum SymbolKind {
+    // a variable that the user of the solver knows about
+    External,
+    Slack,
+    Error,
+    // stands in for a required equality so that the row can be checked for being redundant
+    Dummy,
+}
+
+// ordered by id first so that iterating over rows and cells is deterministic, which keeps the simplex from cycling
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct Symbol {
+    id: usize,
+    kind: SymbolKind,
+}
+
+// constant + the sum of every cell's coefficient times its symbol
+// a row in the tableau is equal to its basic symbol, and a row that is not in the tableau yet is equal to 0
+#[derive(Clone, Debug)]
+struct Row {
+    constant: f64,
+    cells: BTreeMap<Symbol, f64>,
+}
+
+impl Row {
+    fn new(constant: f64) -> Self {
+        Self { constant, cells: BTreeMap::new() }
+    }
+
+    fn coefficient_for(&self, symbol: Symbol) -> f64 {
+        self.cells.get(&symbol).copied().unwrap_or(0.0)
+    }
+
+    fn insert_symbol(&mut self, symbol: Symbol, coefficient: f64) {
+        let new_coefficient = self.coefficient_for(symbol) + coefficient;
+        if near_zero(new_coefficient) {
+            self.cells.remove(&symbol);
+        } else {
+            self.cells.insert(symbol, new_coefficient);
+        }
+    }
+
+    fn insert_row(&mut self, other: &Row, coefficient: f64) {
+        self.constant += other.constant * coefficient;
+        for (&symbol, &other_coefficient) in &other.cells {
+            self.insert_symbol(symbol, other_coefficient * coefficient);
+        }
+    }
+
+    fn reverse_sign(&mut self) {
+        self.constant = -self.constant;
+        for coefficient in self.cells.values_mut() {
+            *coefficient = -*coefficient;
+        }
+    }
+
+    // turns `0 = constant + a * symbol + ...` into `symbol = -constant / a - ...`
+    fn solve_for(&mut self, symbol: Symbol) {
+        let coefficient = -1.0 / self.cells.remove(&symbol).expect("solving for a symbol that is not in the row");
+        self.constant *= coefficient;
+        for cell in self.cells.values_mut() {
+            *cell *= coefficient;
+        }
+    }
+
+    // turns `lhs = ...` into `rhs = ...`
+    fn solve_for_pair(&mut self, lhs: Symbol, rhs: Symbol) {
+        self.insert_symbol(lhs, -1.0);
+        self.solve_for(rhs);
+    }
+
+    fn substitute(&mut self, symbol: Symbol, row: &Row) {
+        if let Some(coefficient) = self.cells.remove(&symbol) {
+            self.insert_row(row, coefficient);
+        }
+    }
+}
+
+// the symbols that were made for a constraint
+struct Tag {
+    marker: Symbol,
+    other: Option<Symbol>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Solver {
+    rows: BTreeMap<Symbol, Row>,
+    variables: HashMap<usize, Symbol>,
+    objective: Row,
+    // only exists while a constraint is being added with an artificial variable
+    artificial: Option<Row>,
+    next_id: usize,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Self { rows: BTreeMap::new(), variables: HashMap::new(), objective: Row::new(0.0), artificial: None, next_id: 0 }
+    }
+
+    // adds the constraint `terms + constant (relation) 0`, where terms are (variable, coefficient)
+    // weight is None for required constraints
+    // if adding the constraint fails then the solver is left how it was before
+    pub fn add_constraint(&mut self, terms: &[(usize, f64)], constant: f64, relation: Relation, weight: Option<f64>) -> Result<(), SolverError> {
+        let before = self.clone();
+        let result = self.add_constraint_inner(terms, constant, relation, weight);
+        if result.is_err() {
+            *self = before;
+        }
+        result
+    }
+
+    // the value of a variable in the current solution
+    // variables that no constraint mentions are 0
+    pub fn value(&self, variable: usize) -> f64 {
+        self.variables.get(&variable).and_then(|symbol| self.rows.get(symbol)).map_or(0.0, |row| row.constant)
+    }
+
+    fn add_constraint_inner(&mut self, terms: &[(usize, f64)], constant: f64, relation: Relation, weight: Option<f64>) -> Result<(), SolverError> {
+        let (mut row, tag) = self.create_row(terms, constant, relation, weight);
+        let subject = match choose_subject(&row, &tag) {
+            Some(subject) => Some(subject),
+            None if row.cells.keys().all(|symbol| symbol.kind == SymbolKind::Dummy) => {
+                if !near_zero(row.constant) {
+                    return Err(SolverError::Unsatisfiable);
+                }
+                // the constraint is redundant
+                Some(tag.marker)
+            }
+            None => None,
+        };
+
+        match subject {
+            Some(subject) => {
+                row.solve_for(subject);
+                self.substitute(subject, &row);
+                self.rows.insert(subject, row);
+            }
+            None => {
+                if !self.add_with_artificial_variable(row)? {
+                    return Err(SolverError::Unsatisfiable);
+                }
+            }
+        }
+
+        self.optimize(false)
+    }
+
+    fn new_symbol(&mut self, kind: SymbolKind) -> Symbol {
+        self.next_id += 1;
+        Symbol { id: self.next_id, kind }
+    }
+
+    fn create_row(&mut self, terms: &[(usize, f64)], constant: f64, relation: Relation, weight: Option<f64>) -> (Row, Tag) {
+        let mut row = Row::new(constant);
+        for &(variable, coefficient) in terms {
+            if near_zero(coefficient) {
+                continue;
+            }
+            let symbol = match self.variables.get(&variable) {
+                Some(symbol) => *symbol,
+                None => {
+                    let symbol = self.new_symbol(SymbolKind::External);
+                    self.variables.insert(variable, symbol);
+                    symbol
+                }
+            };
+            // basic variables have to be replaced by their rows so that only parametric variables are left
+            match self.rows.get(&symbol) {
+                Some(basic_row) => row.insert_row(&basic_row.clone(), coefficient),
+                None => row.insert_symbol(symbol, coefficient),
+            }
+        }
+
+        let tag = match relation {
+            Relation::LessOrEqual | Relation::GreaterOrEqual => {
+                let coefficient = if relation == Relation::LessOrEqual { 1.0 } else { -1.0 };
+                let slack = self.new_symbol(SymbolKind::Slack);
+                row.insert_symbol(slack, coefficient);
+                let other = weight.map(|weight| {
+                    let error = self.new_symbol(SymbolKind::Error);
+                    row.insert_symbol(error, -coefficient);
+                    self.objective.insert_symbol(error, weight);
+                    error
+                });
+                Tag { marker: slack, other }
+            }
+            Relation::Equal => match weight {
+                Some(weight) => {
+                    let error_plus = self.new_symbol(SymbolKind::Error);
+                    let error_minus = self.new_symbol(SymbolKind::Error);
+                    row.insert_symbol(error_plus, -1.0);
+                    row.insert_symbol(error_minus, 1.0);
+                    self.objective.insert_symbol(error_plus, weight);
+                    self.objective.insert_symbol(error_minus, weight);
+                    Tag { marker: error_plus, other: Some(error_minus) }
+                }
+                None => {
+                    let dummy = self.new_symbol(SymbolKind::Dummy);
+                    row.insert_symbol(dummy, 1.0);
+                    Tag { marker: dummy, other: None }
+                }
+            },
+        };
+
+        // the constant of a row has to be positive for the solution to be feasible
+        if row.constant < 0.0 {
+            row.reverse_sign();
+        }
+        (row, tag)
+    }
+
+    // returns whether the row could be satisfied
+    fn add_with_artificial_variable(&mut self, row: Row) -> Result<bool, SolverError> {
+        // minimizing the artificial variable down to 0 means that the row can be satisfied
+        let artificial_symbol = self.new_symbol(SymbolKind::Slack);
+        self.rows.insert(artificial_symbol, row.clone());
+        self.artificial = Some(row);
+        self.optimize(true)?;
+        let satisfied = near_zero(self.artificial.take().map_or(0.0, |artificial| artificial.constant));
+
+        // the artificial variable has to be taken out of the tableau again
+        if let Some(mut row) = self.rows.remove(&artificial_symbol) {
+            if row.cells.is_empty() {
+                return Ok(satisfied);
+            }
+            let Some(entering) = row.cells.keys().copied().find(|symbol| matches!(symbol.kind, SymbolKind::Slack | SymbolKind::Error)) else {
+                return Ok(false);
+            };
+            row.solve_for_pair(artificial_symbol, entering);
+            self.substitute(entering, &row);
+            self.rows.insert(entering, row);
+        }
+        for row in self.rows.values_mut() {
+            row.cells.remove(&artificial_symbol);
+        }
+        self.objective.cells.remove(&artificial_symbol);
+        Ok(satisfied)
+    }
+
+    // replaces the symbol everywhere with the row
+    fn substitute(&mut self, symbol: Symbol, row: &Row) {
+        for basic_row in self.rows.values_mut() {
+            basic_row.substitute(symbol, row);
+        }
+        self.objective.substitute(symbol, row);
+        if let Some(artificial) = &mut self.artificial {
+            artificial.substitute(symbol, row);
+        }
+    }
+
+    // the primal simplex method, on the artificial objective or on the real one
+    fn optimize(&mut self, artificial: bool) -> Result<(), SolverError> {
+        loop {
+            let objective = if artificial { self.artificial.as_ref().expect("no artificial objective") } else { &self.objective };
+            let Some(entering) = objective
+                .cells
+                .iter()
+                .find(|(symbol, coefficient)| symbol.kind != SymbolKind::Dummy && **coefficient < 0.0)
+                .map(|(symbol, _)| *symbol)
+            else {
+                return Ok(());
+            };
+
+            // the row that limits how much the entering symbol can grow the most
+            let mut leaving = None;
+            let mut min_ratio = f64::INFINITY;
+            for (symbol, row) in &self.rows {
+                if symbol.kind == SymbolKind::External {
+                    continue;
+                }
+                let coefficient = row.coefficient_for(entering);
+                if coefficient < 0.0 {
+                    let ratio = -row.constant / coefficient;
+                    if ratio < min_ratio {
+                        min_ratio = ratio;
+                        leaving = Some(*symbol);
+                    }
+                }
+            }
+            let leaving = leaving.ok_or(SolverError::Unbounded)?;
+
+            let mut row = self.rows.remove(&leaving).expect("leaving row is not in the tableau");
+            row.solve_for_pair(leaving, entering);
+            self.substitute(entering, &row);
+            self.rows.insert(entering, row);
+        }
+    }
+}
+
+// the symbol to make basic when adding a row without an artificial variable
+fn choose_subject(row: &Row, tag: &Tag) -> Option<Symbol> {
+    if let Some(symbol) = row.cells.keys().find(|symbol| symbol.kind == SymbolKind::External) {
+        return Some(*symbol);
+    }
+    [Some(tag.marker), tag.other]
+        .into_iter()
+        .flatten()
+        .find(|symbol| matches!(symbol.kind, SymbolKind::Slack | SymbolKind::Error) && row.coefficient_for(*symbol) < 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::Strength, Relation, Solver, SolverError};
+
+    const X: usize = 0;
+    const Y: usize = 1;
+
+    fn assert_near(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1.0e-6, "expected {expected}, got {actual}");
+    }
+
+    #[test]
+    fn required_equalities() {
+        let mut solver = Solver::new();
+        // x = 10 and y = x + 5
+        solver.add_constraint(&[(X, 1.0)], -10.0, Relation::Equal, None).unwrap();
+        solver.add_constraint(&[(Y, 1.0), (X, -1.0)], -5.0, Relation::Equal, None).unwrap();
+        assert_near(solver.value(X), 10.0);
+        assert_near(solver.value(Y), 15.0);
+    }
+
+    #[test]
+    fn required_inequalities() {
+        let mut solver = Solver::new();
+        // x <= 20 while x would rather be 100, and y >= x + 5 while y would rather be 0
+        solver.add_constraint(&[(X, 1.0)], -20.0, Relation::LessOrEqual, None).unwrap();
+        solver.add_constraint(&[(X, 1.0)], -100.0, Relation::Equal, Strength::Weak.weight()).unwrap();
+        solver.add_constraint(&[(Y, 1.0), (X, -1.0)], -5.0, Relation::GreaterOrEqual, None).unwrap();
+        solver.add_constraint(&[(Y, 1.0)], 0.0, Relation::Equal, Strength::Weak.weight()).unwrap();
+        assert_near(solver.value(X), 20.0);
+        assert_near(solver.value(Y), 25.0);
+    }
+
+    #[test]
+    fn conflicting_required_constraints_are_unsatisfiable() {
+        let mut solver = Solver::new();
+        solver.add_constraint(&[(X, 1.0)], -10.0, Relation::Equal, None).unwrap();
+        assert_eq!(solver.add_constraint(&[(X, 1.0)], -20.0, Relation::Equal, None), Err(SolverError::Unsatisfiable));
+        assert_eq!(solver.add_constraint(&[(X, 1.0)], -30.0, Relation::GreaterOrEqual, None), Err(SolverError::Unsatisfiable));
+        assert_eq!(solver.add_constraint(&[(X, 1.0)], -5.0, Relation::LessOrEqual, None), Err(SolverError::Unsatisfiable));
+        assert_near(solver.value(X), 10.0);
+    }
+
+    #[test]
+    fn strong_beats_weak() {
+        for strong_first in [false, true] {
+            let mut solver = Solver::new();
+            let strong = |solver: &mut Solver| solver.add_constraint(&[(X, 1.0)], -20.0, Relation::Equal, Strength::Strong.weight()).unwrap();
+            let weak = |solver: &mut Solver| solver.add_constraint(&[(X, 1.0)], -10.0, Relation::Equal, Strength::Weak.weight()).unwrap();
+            if strong_first {
+                strong(&mut solver);
+                weak(&mut solver);
+            } else {
+                weak(&mut solver);
+                strong(&mut solver);
+            }
+            assert_near(solver.value(X), 20.0);
+        }
+    }
+
+    #[test]
+    fn redundant_constraints_are_allowed() {
+        let mut solver = Solver::new();
+        solver.add_constraint(&[(X, 1.0)], -10.0, Relation::Equal, None).unwrap();
+        solver.add_constraint(&[(Y, 1.0), (X, -1.0)], 0.0, Relation::Equal, None).unwrap();
+        // the same constraints again, written differently
+        solver.add_constraint(&[(X, 2.0)], -20.0, Relation::Equal, None).unwrap();
+        solver.add_constraint(&[(X, 1.0), (Y, -1.0)], 0.0, Relation::Equal, None).unwrap();
+        assert_near(solver.value(X), 10.0);
+        assert_near(solver.value(Y), 10.0);
+    }
+
+    #[test]
+    fn failed_add_constraint_leaves_solver_unchanged() {
+        let mut solver = Solver::new();
+        // x >= 10 while x would rather be 0
+        solver.add_constraint(&[(X, 1.0)], -10.0, Relation::GreaterOrEqual, None).unwrap();
+        solver.add_constraint(&[(X, 1.0)], 0.0, Relation::Equal, Strength::Weak.weight()).unwrap();
+        let before = format!("{solver:?}");
+
+        assert_eq!(solver.add_constraint(&[(X, 1.0)], -5.0, Relation::LessOrEqual, None), Err(SolverError::Unsatisfiable));
+        assert_eq!(format!("{solver:?}"), before);
+        assert_near(solver.value(X), 10.0);
+
+        // the solver still works after the failure
+        solver.add_constraint(&[(Y, 1.0), (X, -1.0)], -1.0, Relation::Equal, None).unwrap();
+        assert_near(solver.value(Y), 11.0);
+    }
+}
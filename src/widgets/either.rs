@@ -1,9 +1,10 @@
 use std::{collections::HashSet, marker::PhantomData};
 
 use crate::{
-    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker},
-    event::{GeneralEvent, TargetedEvent},
-    graphics, layout,
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event::{Event, EventContext},
+    graphics,
+    layout::{self, Direction},
     widgets::Widget,
 };
 
@@ -47,8 +48,15 @@ impl<Data, Left: Widget<Data>, Right: Widget<Data>> Widget<Data> for Either<Data
 impl<Data, Left: ActualWidget<Data>, Right: ActualWidget<Data>> ActualWidget<Data> for EitherActualWidget<Data, Left, Right> {
     fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
         match self {
-            EitherActualWidget::Left(l) => l.layout(graphics_context, sc),
-            EitherActualWidget::Right(r, _) => r.layout(graphics_context, sc),
+            EitherActualWidget::Left(l) => layout::layout_child(l, graphics_context, sc),
+            EitherActualWidget::Right(r, _) => layout::layout_child(r, graphics_context, sc),
+        }
+    }
+
+    fn needs_layout(&self) -> bool {
+        match self {
+            EitherActualWidget::Left(l) => l.needs_layout(),
+            EitherActualWidget::Right(r, _) => r.needs_layout(),
         }
     }
 
@@ -58,10 +66,11 @@ impl<Data, Left: ActualWidget<Data>, Right: ActualWidget<Data>> ActualWidget<Dat
         target: &mut dyn graphics::RenderTarget,
         top_left: graphics::Vector2f,
         hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
     ) {
         match self {
-            EitherActualWidget::Left(l) => l.draw(graphics_context, target, top_left, hover),
-            EitherActualWidget::Right(r, _) => r.draw(graphics_context, target, top_left, hover),
+            EitherActualWidget::Left(l) => l.draw(graphics_context, target, top_left, hover, focus),
+            EitherActualWidget::Right(r, _) => r.draw(graphics_context, target, top_left, hover, focus),
         }
     }
 
@@ -72,6 +81,27 @@ impl<Data, Left: ActualWidget<Data>, Right: ActualWidget<Data>> ActualWidget<Dat
         }
     }
 
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        match self {
+            EitherActualWidget::Left(l) => l.find_focusable(),
+            EitherActualWidget::Right(r, _) => r.find_focusable(),
+        }
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        match self {
+            EitherActualWidget::Left(l) => l.find_path(target),
+            EitherActualWidget::Right(r, _) => r.find_path(target),
+        }
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        match self {
+            EitherActualWidget::Left(l) => l.find_rect(top_left, query),
+            EitherActualWidget::Right(r, _) => r.find_rect(top_left, query),
+        }
+    }
+
     fn size(&self) -> graphics::Vector2f {
         match self {
             EitherActualWidget::Left(l) => l.size(),
@@ -79,18 +109,33 @@ impl<Data, Left: ActualWidget<Data>, Right: ActualWidget<Data>> ActualWidget<Dat
         }
     }
 
-    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: TargetedEvent) {
+    fn baseline(&self) -> Option<f32> {
+        match self {
+            EitherActualWidget::Left(l) => l.baseline(),
+            EitherActualWidget::Right(r, _) => r.baseline(),
+        }
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        match self {
+            EitherActualWidget::Left(l) => l.min_intrinsic_size(graphics_context, direction, cross_size),
+            EitherActualWidget::Right(r, _) => r.min_intrinsic_size(graphics_context, direction, cross_size),
+        }
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
         match self {
-            EitherActualWidget::Left(l) => l.dispatch_event(top_left, data, target, event),
-            EitherActualWidget::Right(r, _) => r.dispatch_event(top_left, data, target, event),
+            EitherActualWidget::Left(l) => l.max_intrinsic_size(graphics_context, direction, cross_size),
+            EitherActualWidget::Right(r, _) => r.max_intrinsic_size(graphics_context, direction, cross_size),
         }
     }
 
-    fn targeted_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: TargetedEvent) {}
-    fn general_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, event: GeneralEvent) {
+    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: Event, ctx: &mut EventContext) {
         match self {
-            EitherActualWidget::Left(l) => l.general_event(top_left, data, event),
-            EitherActualWidget::Right(r, _) => r.general_event(top_left, data, event),
+            EitherActualWidget::Left(l) => l.dispatch_event(top_left, data, target, event, ctx),
+            EitherActualWidget::Right(r, _) => r.dispatch_event(top_left, data, target, event, ctx),
         }
     }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: Event, _: &mut EventContext) {}
 }
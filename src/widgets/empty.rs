@@ -1,8 +1,9 @@
 use std::collections::HashSet;
 
 use crate::{
-    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker},
-    event, graphics, layout,
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
     widgets::Widget,
 };
 
@@ -10,13 +11,14 @@ pub struct Empty;
 
 pub struct EmptyActualWidget {
     size: graphics::Vector2f,
+    layout_cache: layout::LayoutCache,
 }
 
 impl<Data> Widget<Data> for Empty {
     type ActualWidget = EmptyActualWidget;
 
     fn to_actual_widget(self, _: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
-        EmptyActualWidget { size: graphics::Vector2f::new(0.0, 0.0) }
+        EmptyActualWidget { size: graphics::Vector2f::new(0.0, 0.0), layout_cache: layout::LayoutCache::new() }
     }
 
     fn update_actual_widget(self, _: &mut Self::ActualWidget, _: &mut ActualWidgetIdMaker) {}
@@ -24,20 +26,55 @@ impl<Data> Widget<Data> for Empty {
 
 impl<Data> ActualWidget<Data> for EmptyActualWidget {
     fn layout(&mut self, _: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
         self.size = sc.clamp_size(graphics::Vector2f::new(0.0, 0.0));
+        self.layout_cache.laid_out(sc);
     }
 
-    fn draw(&self, _: &graphics::GraphicsContext, _: &mut dyn graphics::RenderTarget, _: graphics::Vector2f, _: &HashSet<ActualWidgetId>) {}
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty()
+    }
+
+    fn draw(
+        &self,
+        _: &graphics::GraphicsContext,
+        _: &mut dyn graphics::RenderTarget,
+        _: graphics::Vector2f,
+        _: &HashSet<ActualWidgetId>,
+        _: Option<ActualWidgetId>,
+    ) {
+    }
 
     fn find_hover(&self, _: graphics::Vector2f, _: graphics::Vector2f) -> Box<(dyn Iterator<Item = (ActualWidgetId, bool)>)> {
         Box::new(std::iter::empty())
     }
 
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn find_path(&self, _: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        None
+    }
+
+    fn find_rect(&self, _: graphics::Vector2f, _: WidgetQuery) -> Option<graphics::FloatRect> {
+        None
+    }
+
     fn size(&self) -> graphics::Vector2f {
         self.size
     }
 
-    fn dispatch_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: ActualWidgetId, _: event::TargetedEvent) {}
-    fn targeted_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::TargetedEvent) {}
-    fn general_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::GeneralEvent) {}
+    fn min_intrinsic_size(&self, _: &graphics::GraphicsContext, _: Direction, _: f32) -> f32 {
+        0.0
+    }
+
+    fn max_intrinsic_size(&self, _: &graphics::GraphicsContext, _: Direction, _: f32) -> f32 {
+        0.0
+    }
+
+    fn dispatch_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: ActualWidgetId, _: event::Event, _: &mut event::EventContext) {}
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::Event, _: &mut event::EventContext) {}
 }
@@ -1,8 +1,9 @@
 use std::{collections::HashSet, marker::PhantomData};
 
 use crate::{
-    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker},
-    event, graphics, layout,
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
     widgets::Widget,
 };
 
@@ -38,7 +39,16 @@ impl<Data, Child: Widget<Data>> Widget<Data> for Expand<Data, Child> {
 
 impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for ExpandActualWidget<Data, Child> {
     fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
-        self.child.layout(graphics_context, layout::SizeConstraints { min: sc.max, max: sc.max });
+        let fill = |min: f32, max: f32| if max.is_finite() { max } else { min };
+        layout::layout_child(
+            &mut self.child,
+            graphics_context,
+            layout::SizeConstraints { min: graphics::Vector2f::new(fill(sc.min.x, sc.max.x), fill(sc.min.y, sc.max.y)), max: sc.max },
+        );
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
     }
 
     fn draw(
@@ -47,24 +57,53 @@ impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for ExpandActualWidget<
         target: &mut dyn graphics::RenderTarget,
         top_left: graphics::Vector2f,
         hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
     ) {
-        self.child.draw(graphics_context, target, top_left, hover);
+        self.child.draw(graphics_context, target, top_left, hover, focus);
     }
 
     fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<(dyn Iterator<Item = (ActualWidgetId, bool)> + '_)> {
         self.child.find_hover(top_left, mouse)
     }
 
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        self.child.find_focusable()
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.child.find_path(target)
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.child.find_rect(top_left, query)
+    }
+
     fn size(&self) -> graphics::Vector2f {
         self.child.size()
     }
 
-    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: event::TargetedEvent) {
-        self.child.dispatch_event(top_left, data, target, event);
+    fn baseline(&self) -> Option<f32> {
+        self.child.baseline()
     }
 
-    fn targeted_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::TargetedEvent) {}
-    fn general_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, event: event::GeneralEvent) {
-        self.child.general_event(top_left, data, event);
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.min_intrinsic_size(graphics_context, direction, cross_size)
     }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.max_intrinsic_size(graphics_context, direction, cross_size)
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        self.child.dispatch_event(top_left, data, target, event, ctx);
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::Event, _: &mut event::EventContext) {}
 }
@@ -1,51 +1,104 @@
-use crate::graphics;
+// Direction is in layout now, this keeps the old path working
+pub use crate::layout::Direction;
+use crate::{actual_widget::animated::Lerpable, graphics};
 
 #[macro_use]
 pub mod fixed_amount;
 pub mod homogeneous;
 
 pub mod _layout {
+    use sfml::graphics::Shape;
+
     use crate::{
         actual_widget::{
             animated::{Animated, AnimatedValue, Lerpable},
-            ActualWidget,
+            util, ActualWidget,
         },
         graphics::{self, GraphicsContext},
-        layout::SizeConstraints,
-        widgets::flex::{Direction, ItemSettings},
+        layout::{layout_child, Direction, SizeConstraints},
+        widgets::flex::{CrossAxisAlignment, ItemSettings, ItemSizing, MainAxisAlignment, Margin, Overflow},
     };
 
+    // the overflow stripe is made of alternating yellow and black segments
+    const OVERFLOW_STRIPE_THICKNESS: f32 = 4.0;
+    const OVERFLOW_STRIPE_SEGMENT: f32 = 8.0;
+
     #[inline]
     pub fn animated_settings(settings: Animated<ItemSettings>) -> ItemSettings {
         match settings.get() {
             AnimatedValue::Steady(s) => *s,
-            AnimatedValue::Animating { before: ItemSettings::Flex(before_flex), after: ItemSettings::Flex(after_flex), amount } => {
-                ItemSettings::Flex(before_flex.lerp(after_flex, amount))
-            }
-            AnimatedValue::Animating { before: _, after, amount: _ } => *after,
+            AnimatedValue::Animating { before, after, amount } => ItemSettings {
+                sizing: match (before.sizing, after.sizing) {
+                    (ItemSizing::Flex(before_flex), ItemSizing::Flex(after_flex)) => ItemSizing::Flex(before_flex.lerp(&after_flex, amount)),
+                    (_, after_sizing) => after_sizing,
+                },
+                margin: before.margin.lerp(&after.margin, amount),
+            },
         }
     }
 
+    // the constraints for a child that can be at most major_max long (not including its margin)
+    fn item_sc(
+        sc: SizeConstraints,
+        direction: Direction,
+        cross_axis_alignment: CrossAxisAlignment,
+        margin: Margin,
+        major_max: f32,
+    ) -> SizeConstraints {
+        let minor_max = (direction.take_minor_component(sc.max) - direction.take_minor_component(margin.total())).max(0.0);
+        // children can only be stretched to a size that is known
+        let minor_min = if cross_axis_alignment == CrossAxisAlignment::Stretch && minor_max.is_finite() { minor_max } else { 0.0 };
+        SizeConstraints { min: direction.make_vector_in_direction(0.0, minor_min), max: direction.make_vector_in_direction(major_max, minor_max) }
+    }
+
     // phase 1 of flex layout: lay out fixed elements and count up total flex scaling factors
     pub fn phase1<'w, 'o, 'c, 'p, Data: 'w>(
         graphics_context: &GraphicsContext,
         sc: SizeConstraints,
         direction: Direction,
+        cross_axis_alignment: CrossAxisAlignment,
+        gap: f32,
+        overflow: Overflow,
         items: impl Iterator<Item = (ItemSettings, &'w mut dyn ActualWidget<Data>)>,
     ) -> (f32, f32) {
+        let mut items: Vec<_> = items.collect();
         let mut total_flex_scale = 0.0;
         let mut major_size_left = direction.take_major_component(sc.max);
-        for (settings, child) in items {
-            match settings {
-                ItemSettings::Fixed => {
-                    child.layout(graphics_context, sc.with_no_min());
+        for (index, (settings, child)) in items.iter_mut().enumerate() {
+            if index != 0 {
+                major_size_left -= gap;
+            }
+            // margins take up space no matter what the item's sizing is
+            let major_margin = direction.take_major_component(settings.margin.total());
+            major_size_left -= major_margin;
+            match settings.sizing {
+                ItemSizing::Fixed => {
+                    let major_max = (direction.take_major_component(sc.max) - major_margin).max(0.0);
+                    layout_child(&mut **child, graphics_context, item_sc(sc, direction, cross_axis_alignment, settings.margin, major_max));
                     major_size_left -= direction.take_major_component(child.size());
                 }
-                ItemSettings::Flex(scale) => {
+                ItemSizing::Flex(scale) => {
                     total_flex_scale += scale;
                 }
             };
         }
+
+        if overflow == Overflow::Shrink && major_size_left < 0.0 {
+            // every fixed item gets shrunk by the same fraction of its size so that they all fit together
+            let is_fixed = |settings: &ItemSettings| settings.sizing == ItemSizing::Fixed;
+            let fixed_size: f32 =
+                items.iter().filter(|(settings, _)| is_fixed(settings)).map(|(_, child)| direction.take_major_component(child.size())).sum();
+            if fixed_size > 0.0 {
+                let fraction = ((fixed_size + major_size_left) / fixed_size).max(0.0);
+                for (settings, child) in items.iter_mut().filter(|(settings, _)| is_fixed(settings)) {
+                    let before = direction.take_major_component(child.size());
+                    layout_child(&mut **child, graphics_context, item_sc(sc, direction, cross_axis_alignment, settings.margin, before * fraction));
+                    // children that cannot get any smaller still overflow
+                    major_size_left += before - direction.take_major_component(child.size());
+                }
+            }
+        }
+
         (total_flex_scale, major_size_left)
     }
 
@@ -54,70 +107,309 @@ pub mod _layout {
         graphics_context: &GraphicsContext,
         sc: SizeConstraints,
         direction: Direction,
+        cross_axis_alignment: CrossAxisAlignment,
         (total_flex_scale, major_size_left): (f32, f32), // phase 1 output
         items: impl Iterator<Item = (ItemSettings, &'w mut dyn ActualWidget<Data>)>,
     ) {
         for (settings, child) in items {
-            if let ItemSettings::Flex(scale) = settings {
-                let child_sc = SizeConstraints {
-                    min: graphics::Vector2f::new(0.0, 0.0),
-                    max: direction.make_vector_in_direction(scale / total_flex_scale * major_size_left, direction.take_minor_component(sc.max)),
+            if let ItemSizing::Flex(scale) = settings.sizing {
+                let major_max = if major_size_left.is_finite() {
+                    // if the fixed items already take up more than all of the space, the flex items get none of it
+                    (scale / total_flex_scale * major_size_left).max(0.0)
+                } else {
+                    // there is no space to share out along an unbounded direction so flex items get sized like fixed items
+                    f32::INFINITY
                 };
-                child.layout(graphics_context, child_sc);
+                layout_child(child, graphics_context, item_sc(sc, direction, cross_axis_alignment, settings.margin, major_max));
             };
         }
     }
 
+    // the intrinsic size of the whole flex along the direction given
+    // measure gets either the min or the max intrinsic size of a child
+    pub fn intrinsic_size<'w, Data: 'w>(
+        flex_direction: Direction,
+        gap: f32,
+        direction: Direction,
+        cross_size: f32,
+        items: impl Iterator<Item = (ItemSettings, &'w dyn ActualWidget<Data>)>,
+        measure: impl Fn(&dyn ActualWidget<Data>, Direction, f32) -> f32,
+    ) -> f32 {
+        let mut total_size = 0.0;
+        let mut biggest_size: f32 = 0.0;
+        let mut item_count: usize = 0;
+        for (settings, child) in items {
+            let margin = settings.margin.total();
+            let child_cross_size = (cross_size - direction.take_minor_component(margin)).max(0.0);
+            let size = measure(child, direction, child_cross_size) + direction.take_major_component(margin);
+            total_size += size;
+            biggest_size = biggest_size.max(size);
+            item_count += 1;
+        }
+
+        if direction == flex_direction {
+            total_size + gap * item_count.saturating_sub(1) as f32
+        } else {
+            biggest_size
+        }
+    }
+
+    // how much space goes before the first item and how much extra space goes between each item
+    pub fn distribute_free_space(main_axis_alignment: MainAxisAlignment, free_space: f32, item_count: usize) -> (f32, f32) {
+        let count = item_count as f32;
+        match main_axis_alignment {
+            MainAxisAlignment::Start => (0.0, 0.0),
+            MainAxisAlignment::Center => (free_space / 2.0, 0.0),
+            MainAxisAlignment::End => (free_space, 0.0),
+            MainAxisAlignment::SpaceBetween if item_count > 1 => (0.0, free_space / (count - 1.0)),
+            MainAxisAlignment::SpaceBetween => (0.0, 0.0),
+            MainAxisAlignment::SpaceAround => (free_space / count / 2.0, free_space / count),
+            MainAxisAlignment::SpaceEvenly => (free_space / (count + 1.0), free_space / (count + 1.0)),
+        }
+    }
+
     // phase 3 of flex layout: assign each of the offsets and calcaulte own_size
+    // also returns how far the items go past the right and bottom of own_size
     pub fn phase3<'w, 'o, 'c, 'p, Data: 'w>(
         sc: SizeConstraints,
         direction: Direction,
-        items: impl Iterator<Item = (&'o mut graphics::Vector2f, &'w mut dyn ActualWidget<Data>)>,
-    ) -> graphics::Vector2f {
-        let mut major_offset = 0.0;
-        let mut max_minor_size = 0.0;
-        for (offset, child) in items {
-            let calculated_offset = direction.make_vector_in_direction(major_offset, 0.0);
-            major_offset += direction.take_major_component(child.size());
-            let item_minor_size = direction.take_minor_component(child.size());
-            max_minor_size = if item_minor_size > max_minor_size { item_minor_size } else { max_minor_size };
-            *offset = calculated_offset;
+        main_axis_alignment: MainAxisAlignment,
+        cross_axis_alignment: CrossAxisAlignment,
+        gap: f32,
+        items: impl Iterator<Item = (Margin, &'o mut graphics::Vector2f, &'w mut dyn ActualWidget<Data>)>,
+    ) -> (graphics::Vector2f, graphics::Vector2f) {
+        let items: Vec<_> = items.collect();
+
+        // the size of each item including its margin
+        let outer_size = |margin: &Margin, child: &dyn ActualWidget<Data>| child.size() + margin.total();
+        let total_gap = gap * items.len().saturating_sub(1) as f32;
+        let total_major_size: f32 =
+            items.iter().map(|(margin, _, child)| direction.take_major_component(outer_size(margin, &**child))).sum::<f32>() + total_gap;
+
+        // baselines only make sense to line up in rows, so in columns this is the same as Start
+        let align_baselines = cross_axis_alignment == CrossAxisAlignment::Baseline && direction == Direction::Horizontal;
+        // how far below the top of the item's margin its baseline is, using the bottom of the item if it has no baseline
+        let item_baseline = |margin: &Margin, child: &dyn ActualWidget<Data>| margin.top + child.baseline().unwrap_or(child.size().y);
+        // every item is moved down so that its baseline lines up with the lowest baseline
+        let max_baseline = items.iter().map(|(margin, _, child)| item_baseline(margin, &**child)).fold(0.0, f32::max);
+        let baseline_offset = |margin: &Margin, child: &dyn ActualWidget<Data>| max_baseline - item_baseline(margin, child);
+
+        let max_minor_size = items
+            .iter()
+            .map(|(margin, _, child)| {
+                let moved_down_by = if align_baselines { baseline_offset(margin, &**child) } else { 0.0 };
+                moved_down_by + direction.take_minor_component(outer_size(margin, &**child))
+            })
+            .fold(0.0, f32::max);
+
+        let major_max = direction.take_major_component(sc.max);
+        let minor_max = direction.take_minor_component(sc.max);
+        let own_size = sc.clamp_size(direction.make_vector_in_direction(
+            // anything other than packing at the start needs free space to distribute, so take up all of the space that there is
+            if main_axis_alignment != MainAxisAlignment::Start && major_max.is_finite() { major_max } else { total_major_size },
+            if cross_axis_alignment == CrossAxisAlignment::Stretch && minor_max.is_finite() { minor_max } else { max_minor_size },
+        ));
+        let own_major_size = direction.take_major_component(own_size);
+        let own_minor_size = direction.take_minor_component(own_size);
+
+        let (leading_space, space_between) = distribute_free_space(main_axis_alignment, (own_major_size - total_major_size).max(0.0), items.len());
+
+        let mut major_offset = leading_space;
+        for (margin, offset, child) in items {
+            let free_minor_space = own_minor_size - direction.take_minor_component(outer_size(&margin, &*child));
+            let minor_offset = match cross_axis_alignment {
+                CrossAxisAlignment::Baseline if align_baselines => baseline_offset(&margin, &*child),
+                CrossAxisAlignment::Start | CrossAxisAlignment::Stretch | CrossAxisAlignment::Baseline => 0.0,
+                CrossAxisAlignment::Center => free_minor_space / 2.0,
+                CrossAxisAlignment::End => free_minor_space,
+            };
+            *offset = direction.make_vector_in_direction(major_offset, minor_offset) + margin.top_left();
+            major_offset += direction.take_major_component(outer_size(&margin, &*child)) + space_between + gap;
+        }
+
+        let overflow = direction.make_vector_in_direction((total_major_size - own_major_size).max(0.0), (max_minor_size - own_minor_size).max(0.0));
+        (own_size, overflow)
+    }
+
+    // draws the children with draw_children, clipping them if they overflow and the overflow setting says to
+    // in debug builds, overflow that is not clipped gets marked with a stripe along the edge that the items go past
+    pub fn draw(
+        graphics_context: &GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        own_size: graphics::Vector2f,
+        (overflow_setting, overflow): (Overflow, graphics::Vector2f),
+        draw_children: impl FnOnce(&mut dyn graphics::RenderTarget, graphics::Vector2f),
+    ) {
+        let overflowing = overflow.x > 0.0 || overflow.y > 0.0;
+        if overflow_setting == Overflow::Clip && overflowing {
+            util::clip(graphics_context, target, graphics::FloatRect::from_vecs(top_left, own_size), draw_children);
+        } else {
+            draw_children(target, top_left);
+        }
+
+        if cfg!(debug_assertions) && overflow_setting != Overflow::Clip {
+            if overflow.x > 0.0 {
+                draw_overflow_stripe(
+                    target,
+                    top_left + graphics::Vector2f::new(own_size.x - OVERFLOW_STRIPE_THICKNESS, 0.0),
+                    Direction::Vertical,
+                    own_size.y,
+                );
+            }
+            if overflow.y > 0.0 {
+                draw_overflow_stripe(
+                    target,
+                    top_left + graphics::Vector2f::new(0.0, own_size.y - OVERFLOW_STRIPE_THICKNESS),
+                    Direction::Horizontal,
+                    own_size.x,
+                );
+            }
+        }
+    }
+
+    fn draw_overflow_stripe(target: &mut dyn graphics::RenderTarget, start: graphics::Vector2f, direction: Direction, length: f32) {
+        let mut position = 0.0;
+        let mut yellow = true;
+        while position < length {
+            let segment_length = OVERFLOW_STRIPE_SEGMENT.min(length - position);
+            let mut segment = graphics::RectangleShape::from_rect(graphics::FloatRect::from_vecs(
+                start + direction.make_vector_in_direction(position, 0.0),
+                direction.make_vector_in_direction(segment_length, OVERFLOW_STRIPE_THICKNESS),
+            ));
+            segment.set_fill_color(if yellow { graphics::Color::rgb(255, 200, 0) } else { graphics::Color::BLACK });
+            target.draw(&segment);
+            position += segment_length;
+            yellow = !yellow;
+        }
+    }
+
+    // clipped children can only be hovered inside of the flex
+    pub fn can_hover(top_left: graphics::Vector2f, own_size: graphics::Vector2f, overflow_setting: Overflow, mouse: graphics::Vector2f) -> bool {
+        overflow_setting != Overflow::Clip || graphics::FloatRect::from_vecs(top_left, own_size).contains(mouse)
+    }
+
+    // logs a warning in debug builds when a flex starts to overflow, naming it by its debug name if it has one
+    // this only happens when the overflow starts because layout happens every frame
+    pub fn report_overflow(
+        debug_name: Option<&str>,
+        direction: Direction,
+        overflow_setting: Overflow,
+        previous_overflow: graphics::Vector2f,
+        overflow: graphics::Vector2f,
+    ) {
+        let was_overflowing = previous_overflow.x > 0.0 || previous_overflow.y > 0.0;
+        let overflowing = overflow.x > 0.0 || overflow.y > 0.0;
+        if cfg!(debug_assertions) && overflow_setting != Overflow::Clip && overflowing && !was_overflowing {
+            let direction_name = match direction {
+                Direction::Horizontal => "horizontal",
+                Direction::Vertical => "vertical",
+            };
+            eprintln!(
+                "warning: {direction_name} flex {:?} overflows by {} px horizontally and {} px vertically",
+                debug_name.unwrap_or("(unnamed)"),
+                overflow.x,
+                overflow.y
+            );
         }
-        sc.clamp_size(direction.make_vector_in_direction(major_offset, max_minor_size))
     }
 }
 
 #[derive(Copy, Clone, PartialEq)]
-pub enum ItemSettings {
+pub struct ItemSettings {
+    pub sizing: ItemSizing,
+    // space around the item that no other item can go in
+    pub margin: Margin,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum ItemSizing {
     Fixed,
     Flex(f32),
 }
 
-#[derive(Copy, Clone)]
-pub enum Direction {
-    Horizontal,
-    Vertical,
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Margin {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
 }
 
-impl Direction {
-    pub fn make_vector_in_direction<T>(&self, major_component: T, minor_component: T) -> graphics::Vector2<T> {
-        match self {
-            Direction::Horizontal => graphics::Vector2::new(major_component, minor_component),
-            Direction::Vertical => graphics::Vector2::new(minor_component, major_component),
-        }
+impl ItemSettings {
+    pub fn fixed() -> ItemSettings {
+        ItemSettings { sizing: ItemSizing::Fixed, margin: Margin::default() }
+    }
+    pub fn flex(scale: f32) -> ItemSettings {
+        ItemSettings { sizing: ItemSizing::Flex(scale), margin: Margin::default() }
     }
 
-    pub fn take_major_component<T>(&self, v: graphics::Vector2<T>) -> T {
-        match self {
-            Direction::Horizontal => v.x,
-            Direction::Vertical => v.y,
-        }
+    pub fn with_margin(self, margin: Margin) -> ItemSettings {
+        ItemSettings { margin, ..self }
+    }
+}
+
+impl Margin {
+    pub fn new(left: f32, top: f32, right: f32, bottom: f32) -> Margin {
+        Margin { left, top, right, bottom }
+    }
+    pub fn all_around(margin: f32) -> Margin {
+        Margin::new(margin, margin, margin, margin)
+    }
+
+    fn top_left(&self) -> graphics::Vector2f {
+        graphics::Vector2f::new(self.left, self.top)
+    }
+    fn total(&self) -> graphics::Vector2f {
+        graphics::Vector2f::new(self.left + self.right, self.top + self.bottom)
     }
+}
 
-    pub fn take_minor_component<T>(&self, v: graphics::Vector2<T>) -> T {
-        match self {
-            Direction::Horizontal => v.y,
-            Direction::Vertical => v.x,
+impl Lerpable for Margin {
+    fn lerp(&self, other: &Self, amount: f64) -> Self {
+        Margin {
+            left: self.left.lerp(&other.left, amount),
+            top: self.top.lerp(&other.top, amount),
+            right: self.right.lerp(&other.right, amount),
+            bottom: self.bottom.lerp(&other.bottom, amount),
         }
     }
 }
+
+// what happens when the items do not fit in the space that the flex is allowed to take up
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Overflow {
+    // the items go past the edges of the flex and get drawn over whatever is next to it
+    #[default]
+    Visible,
+    // the items go past the edges of the flex but the parts outside of the flex are not drawn or hovered
+    Clip,
+    // fixed items are laid out again with a smaller size, each shrunk by the same fraction, and only overflow if they cannot get that small
+    Shrink,
+}
+
+// how children are placed along the direction of the flex
+// anything other than Start makes the flex take up all of the space that it is allowed to
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum MainAxisAlignment {
+    #[default]
+    Start,
+    Center,
+    End,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+// how children are placed perpendicular to the direction of the flex
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum CrossAxisAlignment {
+    #[default]
+    Start,
+    Center,
+    End,
+    // children are forced to be as big as the flex is allowed to be
+    Stretch,
+    // children in a horizontal flex are lined up by the baseline of their first line of text, which is the same as Start in a vertical flex
+    Baseline,
+}
@@ -2,15 +2,20 @@
 #[macro_export]
 macro_rules! flex {
     (horizontal $($rest:tt)*) => {
-        $crate::flex!($crate::widgets::flex::Direction::Horizontal $($rest)*)
+        $crate::flex!($crate::layout::Direction::Horizontal $($rest)*)
     };
     (vertical $($rest:tt)*) => {
-        $crate::flex!($crate::widgets::flex::Direction::Vertical $($rest)*)
+        $crate::flex!($crate::layout::Direction::Vertical $($rest)*)
     };
     ($direction:path { $( $name:ident : ( $settings:expr, $e:expr ) ),* $(,)? }) => {
         {
             #[allow(non_camel_case_types)]
             struct Container<Data, $($name: $crate::widgets::Widget<Data>),*> {
+                main_axis_alignment: $crate::widgets::flex::MainAxisAlignment,
+                cross_axis_alignment: $crate::widgets::flex::CrossAxisAlignment,
+                gap: f32,
+                overflow: $crate::widgets::flex::Overflow,
+                debug_name: ::std::option::Option<&'static str>,
                 $(
                     $name: ($crate::widgets::flex::ItemSettings, $name),
                 )*
@@ -19,6 +24,13 @@ macro_rules! flex {
             #[allow(non_camel_case_types)]
             struct ContainerActualWidget<Data, $($name: $crate::actual_widget::ActualWidget<Data>),*> {
                 own_size: $crate::graphics::Vector2f,
+                overflow_amount: $crate::graphics::Vector2f,
+                layout_cache: $crate::layout::LayoutCache,
+                main_axis_alignment: $crate::widgets::flex::MainAxisAlignment,
+                cross_axis_alignment: $crate::widgets::flex::CrossAxisAlignment,
+                gap: f32,
+                overflow: $crate::widgets::flex::Overflow,
+                debug_name: ::std::option::Option<&'static str>,
                 $(
                     $name: ($crate::actual_widget::animated::Animated<$crate::widgets::flex::ItemSettings>, $crate::graphics::Vector2f, $name),
                 )*
@@ -26,6 +38,29 @@ macro_rules! flex {
                 _phantom: ::std::marker::PhantomData<fn(&mut Data)>,
             }
 
+            #[allow(non_camel_case_types, dead_code)]
+            impl<Data, $($name: $crate::widgets::Widget<Data>),*> Container<Data, $($name),*> {
+                fn with_main_axis_alignment(mut self, main_axis_alignment: $crate::widgets::flex::MainAxisAlignment) -> Self {
+                    self.main_axis_alignment = main_axis_alignment;
+                    self
+                }
+                fn with_cross_axis_alignment(mut self, cross_axis_alignment: $crate::widgets::flex::CrossAxisAlignment) -> Self {
+                    self.cross_axis_alignment = cross_axis_alignment;
+                    self
+                }
+                fn with_gap(mut self, gap: f32) -> Self {
+                    self.gap = gap;
+                    self
+                }
+                fn with_overflow(mut self, overflow: $crate::widgets::flex::Overflow) -> Self {
+                    self.overflow = overflow;
+                    self
+                }
+                fn with_debug_name(mut self, debug_name: &'static str) -> Self {
+                    self.debug_name = ::std::option::Option::Some(debug_name);
+                    self
+                }
+            }
             #[allow(non_camel_case_types)]
             impl<Data, $($name: $crate::widgets::Widget<Data>),*> $crate::widgets::Widget<Data> for Container<Data, $($name),*> {
                 type ActualWidget = ContainerActualWidget<Data, $(<$name as $crate::widgets::Widget<Data>>::ActualWidget),*>;
@@ -33,6 +68,13 @@ macro_rules! flex {
                 fn to_actual_widget(self, id_maker: &mut $crate::actual_widget::ActualWidgetIdMaker) -> Self::ActualWidget {
                     ContainerActualWidget {
                         own_size: $crate::graphics::Vector2f::new(0.0, 0.0),
+                        overflow_amount: $crate::graphics::Vector2f::new(0.0, 0.0),
+                        layout_cache: $crate::layout::LayoutCache::new(),
+                        main_axis_alignment: self.main_axis_alignment,
+                        cross_axis_alignment: self.cross_axis_alignment,
+                        gap: self.gap,
+                        overflow: self.overflow,
+                        debug_name: self.debug_name,
                         $(
                             $name: ($crate::actual_widget::animated::Animated::new(self.$name.0), $crate::graphics::Vector2f::new(0.0, 0.0), self.$name.1.to_actual_widget(id_maker)),
                         )*
@@ -41,6 +83,12 @@ macro_rules! flex {
                 }
 
                 fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut $crate::actual_widget::ActualWidgetIdMaker) {
+                    let cache = &mut actual_widget.layout_cache;
+                    cache.update(&mut actual_widget.main_axis_alignment, self.main_axis_alignment);
+                    cache.update(&mut actual_widget.cross_axis_alignment, self.cross_axis_alignment);
+                    cache.update(&mut actual_widget.gap, self.gap);
+                    cache.update(&mut actual_widget.overflow, self.overflow);
+                    actual_widget.debug_name = self.debug_name;
                     $(
                         actual_widget.$name.0.set(self.$name.0);
                         self.$name.1.update_actual_widget(&mut actual_widget.$name.2, id_maker);
@@ -50,21 +98,37 @@ macro_rules! flex {
             #[allow(non_camel_case_types)]
             impl<Data, $($name: $crate::actual_widget::ActualWidget<Data>),*> $crate::actual_widget::ActualWidget<Data> for ContainerActualWidget<Data, $($name),*> {
                 fn layout(&mut self, graphics_context: &$crate::graphics::GraphicsContext, sc: $crate::layout::SizeConstraints) {
-                    let phase1_result = $crate::widgets::flex::_layout::phase1(graphics_context, sc, $direction, [$(($crate::widgets::flex::_layout::animated_settings(self.$name.0), &mut self.$name.2 as &mut dyn $crate::actual_widget::ActualWidget<Data>)),*].into_iter());
-                    $crate::widgets::flex::_layout::phase2(graphics_context, sc, $direction, phase1_result, [$(($crate::widgets::flex::_layout::animated_settings(self.$name.0), &mut self.$name.2 as &mut dyn $crate::actual_widget::ActualWidget<Data>)),*].into_iter());
-                    self.own_size = $crate::widgets::flex::_layout::phase3(sc, $direction, [$((&mut self.$name.1, &mut self.$name.2 as &mut dyn $crate::actual_widget::ActualWidget<Data>)),*].into_iter());
+                    if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+                        return;
+                    }
+                    let phase1_result = $crate::widgets::flex::_layout::phase1(graphics_context, sc, $direction, self.cross_axis_alignment, self.gap, self.overflow, [$(($crate::widgets::flex::_layout::animated_settings(self.$name.0), &mut self.$name.2 as &mut dyn $crate::actual_widget::ActualWidget<Data>)),*].into_iter());
+                    $crate::widgets::flex::_layout::phase2(graphics_context, sc, $direction, self.cross_axis_alignment, phase1_result, [$(($crate::widgets::flex::_layout::animated_settings(self.$name.0), &mut self.$name.2 as &mut dyn $crate::actual_widget::ActualWidget<Data>)),*].into_iter());
+                    let (own_size, overflow_amount) = $crate::widgets::flex::_layout::phase3(sc, $direction, self.main_axis_alignment, self.cross_axis_alignment, self.gap, [$(($crate::widgets::flex::_layout::animated_settings(self.$name.0).margin, &mut self.$name.1, &mut self.$name.2 as &mut dyn $crate::actual_widget::ActualWidget<Data>)),*].into_iter());
+                    $crate::widgets::flex::_layout::report_overflow(self.debug_name, $direction, self.overflow, self.overflow_amount, overflow_amount);
+                    self.own_size = own_size;
+                    self.overflow_amount = overflow_amount;
+                    self.layout_cache.laid_out_animating(sc, false $(|| self.$name.0.is_animating())*);
                 }
 
-                fn draw(&self, graphics_context: &$crate::graphics::GraphicsContext, target: &mut dyn $crate::graphics::RenderTarget, top_left: $crate::graphics::Vector2f, hover: &::std::collections::HashSet<$crate::actual_widget::ActualWidgetId>) {
-                    $(
-                        {
-                            let (_, offset, child) = &self.$name;
-                            child.draw(graphics_context, target, top_left + *offset, hover);
-                        }
-                    )*
+                fn needs_layout(&self) -> bool {
+                    self.layout_cache.is_dirty_or_animating(false $(|| self.$name.0.is_animating())*) $(|| self.$name.2.needs_layout())*
+                }
+
+                fn draw(&self, graphics_context: &$crate::graphics::GraphicsContext, target: &mut dyn $crate::graphics::RenderTarget, top_left: $crate::graphics::Vector2f, hover: &::std::collections::HashSet<$crate::actual_widget::ActualWidgetId>, focus: ::std::option::Option<$crate::actual_widget::ActualWidgetId>) {
+                    $crate::widgets::flex::_layout::draw(graphics_context, target, top_left, self.own_size, (self.overflow, self.overflow_amount), |target, top_left| {
+                        $(
+                            {
+                                let (_, offset, child) = &self.$name;
+                                child.draw(graphics_context, target, top_left + *offset, hover, focus);
+                            }
+                        )*
+                    });
                 }
 
                 fn find_hover(&self, top_left: $crate::graphics::Vector2f, mouse: $crate::graphics::Vector2f) -> ::std::boxed::Box<dyn ::std::iter::Iterator<Item = ($crate::actual_widget::ActualWidgetId, bool)> + '_> {
+                    if !$crate::widgets::flex::_layout::can_hover(top_left, self.own_size, self.overflow, mouse) {
+                        return ::std::boxed::Box::new(::std::iter::empty());
+                    }
                     ::std::boxed::Box::new(
                         ::std::iter::empty()
                             $(
@@ -76,25 +140,61 @@ macro_rules! flex {
                     )
                 }
 
+                fn find_focusable(&self) -> ::std::boxed::Box<dyn ::std::iter::Iterator<Item = $crate::actual_widget::ActualWidgetId> + '_> {
+                    ::std::boxed::Box::new(
+                        ::std::iter::empty()
+                            $(
+                                .chain(self.$name.2.find_focusable())
+                            )*
+                    )
+                }
+
+                fn find_path(&self, target: $crate::actual_widget::ActualWidgetId) -> ::std::option::Option<::std::vec::Vec<$crate::actual_widget::ActualWidgetId>> {
+                    ::std::option::Option::None
+                        $(
+                            .or_else(|| self.$name.2.find_path(target))
+                        )*
+                }
+                fn find_rect(&self, top_left: $crate::graphics::Vector2f, query: $crate::actual_widget::WidgetQuery) -> ::std::option::Option<$crate::graphics::FloatRect> {
+                    ::std::option::Option::None
+                        $(
+                            .or_else(|| self.$name.2.find_rect(top_left + self.$name.1, query))
+                        )*
+                }
+
                 fn size(&self) -> $crate::graphics::Vector2f {
                     self.own_size
                 }
 
-                fn dispatch_event(&mut self, top_left: $crate::graphics::Vector2f, data: &mut Data, target: $crate::actual_widget::ActualWidgetId, event: $crate::event::TargetedEvent) {
-                    $(
-                        self.$name.2.dispatch_event(top_left + self.$name.1, data, target, event);
-                    )*
+                fn baseline(&self) -> ::std::option::Option<f32> {
+                    ::std::option::Option::None
+                        $(
+                            .or_else(|| self.$name.2.baseline().map(|baseline| self.$name.1.y + baseline))
+                        )*
                 }
 
-                fn targeted_event(&mut self, _: $crate::graphics::Vector2f, _: &mut Data, _: $crate::event::TargetedEvent) {}
-                fn general_event(&mut self, top_left: $crate::graphics::Vector2f, data: &mut Data, event: $crate::event::GeneralEvent) {
+                fn min_intrinsic_size(&self, graphics_context: &$crate::graphics::GraphicsContext, direction: $crate::layout::Direction, cross_size: f32) -> f32 {
+                    $crate::widgets::flex::_layout::intrinsic_size($direction, self.gap, direction, cross_size, [$(($crate::widgets::flex::_layout::animated_settings(self.$name.0), &self.$name.2 as &dyn $crate::actual_widget::ActualWidget<Data>)),*].into_iter(), |child, direction, cross_size| child.min_intrinsic_size(graphics_context, direction, cross_size))
+                }
+                fn max_intrinsic_size(&self, graphics_context: &$crate::graphics::GraphicsContext, direction: $crate::layout::Direction, cross_size: f32) -> f32 {
+                    $crate::widgets::flex::_layout::intrinsic_size($direction, self.gap, direction, cross_size, [$(($crate::widgets::flex::_layout::animated_settings(self.$name.0), &self.$name.2 as &dyn $crate::actual_widget::ActualWidget<Data>)),*].into_iter(), |child, direction, cross_size| child.max_intrinsic_size(graphics_context, direction, cross_size))
+                }
+
+                fn dispatch_event(&mut self, top_left: $crate::graphics::Vector2f, data: &mut Data, target: $crate::actual_widget::ActualWidgetId, event: $crate::event::Event, ctx: &mut $crate::event::EventContext) {
                     $(
-                        self.$name.2.general_event(top_left + self.$name.1, data, event);
-                     )*
+                        self.$name.2.dispatch_event(top_left + self.$name.1, data, target, event, ctx);
+                    )*
                 }
+
+                fn handle_event(&mut self, _: $crate::graphics::Vector2f, _: &mut Data, _: $crate::event::Event, _: &mut $crate::event::EventContext) {}
             }
 
             Container {
+                main_axis_alignment: $crate::widgets::flex::MainAxisAlignment::Start,
+                cross_axis_alignment: $crate::widgets::flex::CrossAxisAlignment::Start,
+                gap: 0.0,
+                overflow: $crate::widgets::flex::Overflow::Visible,
+                debug_name: ::std::option::Option::None,
                 $(
                     $name: ($settings, $e),
                 )*
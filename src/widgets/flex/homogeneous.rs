@@ -1,41 +1,87 @@
 use std::{collections::HashSet, marker::PhantomData};
 
 use crate::{
-    actual_widget::{animated::Animated, ActualWidget, ActualWidgetId, ActualWidgetIdMaker},
-    event::{GeneralEvent, TargetedEvent},
-    graphics, layout,
+    actual_widget::{animated::Animated, ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event::{Event, EventContext},
+    graphics,
+    layout::{self, Direction},
     widgets::{
-        flex::{Direction, ItemSettings, _layout},
+        flex::{_layout, CrossAxisAlignment, ItemSettings, MainAxisAlignment, Overflow},
         Widget,
     },
 };
 
-pub(crate) struct Flex<Data, Child: Widget<Data>> {
+pub struct Flex<Data, Child: Widget<Data>> {
     direction: Direction,
+    main_axis_alignment: MainAxisAlignment,
+    cross_axis_alignment: CrossAxisAlignment,
+    gap: f32,
+    overflow: Overflow,
+    debug_name: Option<&'static str>,
     children: Vec<(ItemSettings, Child)>,
 
     _phantom: PhantomData<fn(&mut Data)>,
 }
-pub(crate) struct FlexActualWidget<Data, Child: ActualWidget<Data>> {
+pub struct FlexActualWidget<Data, Child: ActualWidget<Data>> {
     direction: Direction,
+    main_axis_alignment: MainAxisAlignment,
+    cross_axis_alignment: CrossAxisAlignment,
+    gap: f32,
+    overflow: Overflow,
+    debug_name: Option<&'static str>,
     children: Vec<(Animated<ItemSettings>, graphics::Vector2f, Child)>,
 
     own_size: graphics::Vector2f,
+    // how far the children went past the edges in the last layout
+    overflow_amount: graphics::Vector2f,
+    layout_cache: layout::LayoutCache,
 
     _phantom: PhantomData<fn(&mut Data)>,
     _private: (),
 }
 
 impl<Data, Child: Widget<Data>> Flex<Data, Child> {
-    pub(crate) fn new(direction: Direction, children: Vec<(ItemSettings, Child)>) -> Self {
-        Self { direction, children, _phantom: PhantomData }
+    pub fn new(direction: Direction, children: Vec<(ItemSettings, Child)>) -> Self {
+        Self {
+            direction,
+            main_axis_alignment: MainAxisAlignment::default(),
+            cross_axis_alignment: CrossAxisAlignment::default(),
+            gap: 0.0,
+            overflow: Overflow::default(),
+            debug_name: None,
+            children,
+            _phantom: PhantomData,
+        }
     }
-    pub(crate) fn new_horizontal(children: Vec<(ItemSettings, Child)>) -> Self {
+    pub fn new_horizontal(children: Vec<(ItemSettings, Child)>) -> Self {
         Self::new(Direction::Horizontal, children)
     }
-    pub(crate) fn new_vertical(children: Vec<(ItemSettings, Child)>) -> Self {
+    pub fn new_vertical(children: Vec<(ItemSettings, Child)>) -> Self {
         Self::new(Direction::Vertical, children)
     }
+
+    pub fn with_main_axis_alignment(mut self, main_axis_alignment: MainAxisAlignment) -> Self {
+        self.main_axis_alignment = main_axis_alignment;
+        self
+    }
+    pub fn with_cross_axis_alignment(mut self, cross_axis_alignment: CrossAxisAlignment) -> Self {
+        self.cross_axis_alignment = cross_axis_alignment;
+        self
+    }
+    // space between each pair of neighboring children
+    pub fn with_gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self
+    }
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+    // the name that overflow warnings use for this flex
+    pub fn with_debug_name(mut self, debug_name: &'static str) -> Self {
+        self.debug_name = Some(debug_name);
+        self
+    }
 }
 
 impl<Data, Child: Widget<Data>> Widget<Data> for Flex<Data, Child> {
@@ -44,18 +90,36 @@ impl<Data, Child: Widget<Data>> Widget<Data> for Flex<Data, Child> {
     fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
         FlexActualWidget {
             direction: self.direction,
+            main_axis_alignment: self.main_axis_alignment,
+            cross_axis_alignment: self.cross_axis_alignment,
+            gap: self.gap,
+            overflow: self.overflow,
+            debug_name: self.debug_name,
             children: self
                 .children
                 .into_iter()
                 .map(|(settings, child)| (Animated::new(settings), graphics::Vector2f::new(0.0, 0.0), child.to_actual_widget(id_maker)))
                 .collect(),
             own_size: graphics::Vector2f::new(0.0, 0.0),
+            overflow_amount: graphics::Vector2f::new(0.0, 0.0),
+            layout_cache: layout::LayoutCache::new(),
             _phantom: PhantomData,
             _private: (),
         }
     }
 
     fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        let cache = &mut actual_widget.layout_cache;
+        cache.update(&mut actual_widget.direction, self.direction);
+        cache.update(&mut actual_widget.main_axis_alignment, self.main_axis_alignment);
+        cache.update(&mut actual_widget.cross_axis_alignment, self.cross_axis_alignment);
+        cache.update(&mut actual_widget.gap, self.gap);
+        cache.update(&mut actual_widget.overflow, self.overflow);
+        actual_widget.debug_name = self.debug_name;
+        if self.children.len() != actual_widget.children.len() {
+            cache.mark_dirty();
+        }
+
         let ro_children = std::mem::take(&mut actual_widget.children);
         let ro_children_infinite = ro_children.into_iter().map(Some).chain(std::iter::repeat_with(|| None));
 
@@ -76,26 +140,51 @@ impl<Data, Child: Widget<Data>> Widget<Data> for Flex<Data, Child> {
         actual_widget.children = new_ro_children;
     }
 }
+impl<Data, Child: ActualWidget<Data>> FlexActualWidget<Data, Child> {
+    fn is_animating(&self) -> bool {
+        self.children.iter().any(|(settings, _, _)| settings.is_animating())
+    }
+}
 impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for FlexActualWidget<Data, Child> {
     fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
         let phase1_result = _layout::phase1(
             graphics_context,
             sc,
             self.direction,
+            self.cross_axis_alignment,
+            self.gap,
+            self.overflow,
             self.children.iter_mut().map(move |(settings, _, child)| (_layout::animated_settings(*settings), child as &mut dyn ActualWidget<Data>)),
         );
         _layout::phase2(
             graphics_context,
             sc,
             self.direction,
+            self.cross_axis_alignment,
             phase1_result,
             self.children.iter_mut().map(move |(settings, _, child)| (_layout::animated_settings(*settings), child as &mut dyn ActualWidget<Data>)),
         );
-        self.own_size = _layout::phase3(
+        let (own_size, overflow_amount) = _layout::phase3(
             sc,
             self.direction,
-            self.children.iter_mut().map(move |(_, offset, child)| (offset, child as &mut dyn ActualWidget<Data>)),
+            self.main_axis_alignment,
+            self.cross_axis_alignment,
+            self.gap,
+            self.children
+                .iter_mut()
+                .map(move |(settings, offset, child)| (_layout::animated_settings(*settings).margin, offset, child as &mut dyn ActualWidget<Data>)),
         );
+        _layout::report_overflow(self.debug_name, self.direction, self.overflow, self.overflow_amount, overflow_amount);
+        self.own_size = own_size;
+        self.overflow_amount = overflow_amount;
+        self.layout_cache.laid_out_animating(sc, self.is_animating());
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty_or_animating(self.is_animating()) || self.children.iter().any(|(_, _, child)| child.needs_layout())
     }
 
     fn draw(
@@ -104,30 +193,69 @@ impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for FlexActualWidget<Da
         target: &mut dyn graphics::RenderTarget,
         top_left: graphics::Vector2f,
         hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
     ) {
-        for (_, offset, child) in &self.children {
-            child.draw(graphics_context, target, top_left + *offset, hover);
-        }
+        _layout::draw(graphics_context, target, top_left, self.own_size, (self.overflow, self.overflow_amount), |target, top_left| {
+            for (_, offset, child) in &self.children {
+                child.draw(graphics_context, target, top_left + *offset, hover, focus);
+            }
+        });
     }
 
     fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<(dyn Iterator<Item = (ActualWidgetId, bool)> + '_)> {
+        if !_layout::can_hover(top_left, self.own_size, self.overflow, mouse) {
+            return Box::new(std::iter::empty());
+        }
         Box::new(self.children.iter().flat_map(move |(_, offset, child)| child.find_hover(top_left + *offset, mouse)))
     }
 
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        Box::new(self.children.iter().flat_map(|(_, _, child)| child.find_focusable()))
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.children.iter().find_map(|(_, _, child)| child.find_path(target))
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.children.iter().find_map(|(_, offset, child)| child.find_rect(top_left + *offset, query))
+    }
+
     fn size(&self) -> graphics::Vector2f {
         self.own_size
     }
 
-    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: TargetedEvent) {
-        for (_, offset, child) in &mut self.children {
-            child.dispatch_event(top_left + *offset, data, target, event);
-        }
+    fn baseline(&self) -> Option<f32> {
+        self.children.iter().find_map(|(_, offset, child)| child.baseline().map(|baseline| offset.y + baseline))
     }
 
-    fn targeted_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: TargetedEvent) {}
-    fn general_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, event: GeneralEvent) {
-        for (_, _, child) in &mut self.children {
-            child.general_event(top_left, data, event);
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        _layout::intrinsic_size(
+            self.direction,
+            self.gap,
+            direction,
+            cross_size,
+            self.children.iter().map(|(settings, _, child)| (_layout::animated_settings(*settings), child as &dyn ActualWidget<Data>)),
+            |child, direction, cross_size| child.min_intrinsic_size(graphics_context, direction, cross_size),
+        )
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        _layout::intrinsic_size(
+            self.direction,
+            self.gap,
+            direction,
+            cross_size,
+            self.children.iter().map(|(settings, _, child)| (_layout::animated_settings(*settings), child as &dyn ActualWidget<Data>)),
+            |child, direction, cross_size| child.max_intrinsic_size(graphics_context, direction, cross_size),
+        )
+    }
+
+    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: Event, ctx: &mut EventContext) {
+        for (_, offset, child) in &mut self.children {
+            child.dispatch_event(top_left + *offset, data, target, event, ctx);
         }
     }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: Event, _: &mut EventContext) {}
 }
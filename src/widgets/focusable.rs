@@ -0,0 +1,132 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use sfml::graphics::Shape;
+
+use crate::{
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
+    widgets::Widget,
+};
+
+pub struct Focusable<Data, Child: Widget<Data>> {
+    child: Child,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+pub struct FocusableActualWidget<Data, Child: ActualWidget<Data>> {
+    id: ActualWidgetId,
+    child: Child,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+    _private: (),
+}
+
+impl<Data, Child: Widget<Data>> Focusable<Data, Child> {
+    pub fn new(child: Child) -> Self {
+        Self { child, _phantom: PhantomData }
+    }
+}
+
+impl<Data, Child: Widget<Data>> Widget<Data> for Focusable<Data, Child> {
+    type ActualWidget = FocusableActualWidget<Data, <Child as Widget<Data>>::ActualWidget>;
+
+    fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        FocusableActualWidget { id: id_maker.next_id(), child: self.child.to_actual_widget(id_maker), _phantom: PhantomData, _private: () }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        self.child.update_actual_widget(&mut actual_widget.child, id_maker);
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for FocusableActualWidget<Data, Child> {
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        layout::layout_child(&mut self.child, graphics_context, sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn draw(
+        &self,
+        graphics_context: &graphics::GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
+    ) {
+        self.child.draw(graphics_context, target, top_left, hover, focus);
+
+        if focus == Some(self.id) {
+            let mut focus_ring = graphics::RectangleShape::from_rect(graphics::FloatRect::from_vecs(top_left, self.size()));
+            focus_ring.set_fill_color(graphics::Color::TRANSPARENT);
+            focus_ring.set_outline_color(graphics::Color::rgb(100, 150, 255)); // TODO: pick a better color for this
+            focus_ring.set_outline_thickness(-2.0);
+            target.draw(&focus_ring);
+        }
+    }
+
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_> {
+        Box::new(
+            if graphics::FloatRect::from_vecs(top_left, self.size()).contains(mouse) { Some((self.id, true)) } else { None }
+                .into_iter()
+                .chain(self.child.find_hover(top_left, mouse)),
+        )
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        Box::new(std::iter::once(self.id).chain(self.child.find_focusable()))
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        if target == self.id {
+            Some(vec![self.id])
+        } else {
+            self.child.find_path(target).map(|path| std::iter::once(self.id).chain(path).collect())
+        }
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        if query.matches_id(self.id) {
+            Some(graphics::FloatRect::from_vecs(top_left, self.size()))
+        } else {
+            self.child.find_rect(top_left, query)
+        }
+    }
+
+    fn size(&self) -> graphics::Vector2f {
+        self.child.size()
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.child.baseline()
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.min_intrinsic_size(graphics_context, direction, cross_size)
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.max_intrinsic_size(graphics_context, direction, cross_size)
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        if target == self.id {
+            self.handle_event(top_left, data, event, ctx);
+        }
+
+        self.child.dispatch_event(top_left, data, target, event, ctx);
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::Event, _: &mut event::EventContext) {}
+}
@@ -0,0 +1,175 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use crate::{
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
+    widgets::Widget,
+};
+
+// makes the child a fraction of the largest size it is allowed to be
+// a direction without a fraction passes the constraints through unchanged, and so does a direction with no limit
+pub struct FractionallySized<Data, Child: Widget<Data>> {
+    child: Child,
+    width_fraction: Option<f32>,
+    height_fraction: Option<f32>,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+pub struct FractionallySizedActualWidget<Data, Child: ActualWidget<Data>> {
+    child: Child,
+    width_fraction: Option<f32>,
+    height_fraction: Option<f32>,
+    layout_cache: layout::LayoutCache,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+impl<Data, Child: Widget<Data>> FractionallySized<Data, Child> {
+    pub fn new(child: Child, width_fraction: f32, height_fraction: f32) -> Self {
+        Self::new_with_options(child, Some(width_fraction), Some(height_fraction))
+    }
+    pub fn new_width(child: Child, width_fraction: f32) -> Self {
+        Self::new_with_options(child, Some(width_fraction), None)
+    }
+    pub fn new_height(child: Child, height_fraction: f32) -> Self {
+        Self::new_with_options(child, None, Some(height_fraction))
+    }
+
+    fn new_with_options(child: Child, width_fraction: Option<f32>, height_fraction: Option<f32>) -> Self {
+        for fraction in [width_fraction, height_fraction].into_iter().flatten() {
+            // the intrinsic sizes divide by the fraction
+            assert!(fraction.is_finite() && fraction > 0.0, "size fraction has to be positive and finite but is {fraction}");
+        }
+        Self { child, width_fraction, height_fraction, _phantom: PhantomData }
+    }
+}
+
+impl<Data, Child: Widget<Data>> Widget<Data> for FractionallySized<Data, Child> {
+    type ActualWidget = FractionallySizedActualWidget<Data, <Child as Widget<Data>>::ActualWidget>;
+
+    fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        FractionallySizedActualWidget {
+            child: self.child.to_actual_widget(id_maker),
+            width_fraction: self.width_fraction,
+            height_fraction: self.height_fraction,
+            layout_cache: layout::LayoutCache::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        actual_widget.layout_cache.update(&mut actual_widget.width_fraction, self.width_fraction);
+        actual_widget.layout_cache.update(&mut actual_widget.height_fraction, self.height_fraction);
+        self.child.update_actual_widget(&mut actual_widget.child, id_maker);
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> FractionallySizedActualWidget<Data, Child> {
+    fn fraction(&self, direction: Direction) -> Option<f32> {
+        match direction {
+            Direction::Horizontal => self.width_fraction,
+            Direction::Vertical => self.height_fraction,
+        }
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for FractionallySizedActualWidget<Data, Child> {
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        // (min, max) along one direction
+        let axis_constraints = |min: f32, max: f32, fraction: Option<f32>| match fraction {
+            Some(fraction) if max.is_finite() => {
+                let size = (max * fraction).clamp(min, max);
+                (size, size)
+            }
+            _ => (min, max),
+        };
+        let (min_width, max_width) = axis_constraints(sc.min.x, sc.max.x, self.width_fraction);
+        let (min_height, max_height) = axis_constraints(sc.min.y, sc.max.y, self.height_fraction);
+        layout::layout_child(
+            &mut self.child,
+            graphics_context,
+            layout::SizeConstraints { min: graphics::Vector2f::new(min_width, min_height), max: graphics::Vector2f::new(max_width, max_height) },
+        );
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.child.needs_layout()
+    }
+
+    fn draw(
+        &self,
+        graphics_context: &graphics::GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
+    ) {
+        self.child.draw(graphics_context, target, top_left, hover, focus);
+    }
+
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_> {
+        self.child.find_hover(top_left, mouse)
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        self.child.find_focusable()
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.child.find_path(target)
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.child.find_rect(top_left, query)
+    }
+
+    fn size(&self) -> graphics::Vector2f {
+        self.child.size()
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.child.baseline()
+    }
+
+    // the parent has to be 1 / fraction times bigger than the child for the child to get the size it wants
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        let child_cross_size = cross_size * self.fraction(direction.perpendicular()).unwrap_or(1.0);
+        self.child.min_intrinsic_size(graphics_context, direction, child_cross_size) / self.fraction(direction).unwrap_or(1.0)
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        let child_cross_size = cross_size * self.fraction(direction.perpendicular()).unwrap_or(1.0);
+        self.child.max_intrinsic_size(graphics_context, direction, child_cross_size) / self.fraction(direction).unwrap_or(1.0)
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        self.child.dispatch_event(top_left, data, target, event, ctx);
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::Event, _: &mut event::EventContext) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FractionallySized;
+    use crate::widgets::empty::Empty;
+
+    #[test]
+    #[should_panic(expected = "size fraction has to be positive")]
+    fn zero_fraction_is_rejected() {
+        let _: FractionallySized<(), Empty> = FractionallySized::new_width(Empty, 0.0);
+    }
+}
@@ -0,0 +1,351 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use crate::{
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event::{Event, EventContext},
+    graphics,
+    layout::{self, Direction},
+    widgets::Widget,
+};
+
+// the size of a single row or column
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Track {
+    Fixed(f32),
+    // shares the space left over after all of the other tracks are sized, in proportion to the fraction, like ItemSizing::Flex
+    // if there is no limit on the size of the grid, there is no space to share so this sizes to the content like Auto
+    Fraction(f32),
+    // as big as the biggest child in the track
+    Auto,
+}
+
+// where a child goes inside of its cell along one axis
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum CellAlignment {
+    #[default]
+    Start,
+    Center,
+    End,
+    Stretch,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct GridItem {
+    pub row: usize,
+    pub column: usize,
+    pub row_span: usize,
+    pub column_span: usize,
+    pub horizontal_alignment: CellAlignment,
+    pub vertical_alignment: CellAlignment,
+}
+
+impl GridItem {
+    pub fn new(row: usize, column: usize) -> Self {
+        Self {
+            row,
+            column,
+            row_span: 1,
+            column_span: 1,
+            horizontal_alignment: CellAlignment::default(),
+            vertical_alignment: CellAlignment::default(),
+        }
+    }
+
+    pub fn with_span(self, row_span: usize, column_span: usize) -> Self {
+        assert!(row_span > 0 && column_span > 0, "grid items have to span at least one row and one column");
+        Self { row_span, column_span, ..self }
+    }
+
+    pub fn with_alignment(self, horizontal_alignment: CellAlignment, vertical_alignment: CellAlignment) -> Self {
+        Self { horizontal_alignment, vertical_alignment, ..self }
+    }
+}
+
+// lays children out in rows and columns so that everything in the same column lines up
+// children placed past the last row or column that was given get extra Auto tracks
+pub struct Grid<Data, Child: Widget<Data>> {
+    rows: Vec<Track>,
+    columns: Vec<Track>,
+    row_gap: f32,
+    column_gap: f32,
+    children: Vec<(GridItem, Child)>,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+pub struct GridActualWidget<Data, Child: ActualWidget<Data>> {
+    rows: Vec<Track>,
+    columns: Vec<Track>,
+    row_gap: f32,
+    column_gap: f32,
+    children: Vec<(GridItem, graphics::Vector2f, Child)>,
+
+    own_size: graphics::Vector2f,
+    layout_cache: layout::LayoutCache,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+    _private: (),
+}
+
+impl<Data, Child: Widget<Data>> Grid<Data, Child> {
+    pub fn new(rows: Vec<Track>, columns: Vec<Track>, children: Vec<(GridItem, Child)>) -> Self {
+        Self { rows, columns, row_gap: 0.0, column_gap: 0.0, children, _phantom: PhantomData }
+    }
+
+    // space between neighboring rows and between neighboring columns
+    pub fn with_gap(self, gap: f32) -> Self {
+        self.with_row_gap(gap).with_column_gap(gap)
+    }
+    pub fn with_row_gap(mut self, row_gap: f32) -> Self {
+        self.row_gap = row_gap;
+        self
+    }
+    pub fn with_column_gap(mut self, column_gap: f32) -> Self {
+        self.column_gap = column_gap;
+        self
+    }
+}
+
+impl<Data, Child: Widget<Data>> Widget<Data> for Grid<Data, Child> {
+    type ActualWidget = GridActualWidget<Data, <Child as Widget<Data>>::ActualWidget>;
+
+    fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        GridActualWidget {
+            rows: self.rows,
+            columns: self.columns,
+            row_gap: self.row_gap,
+            column_gap: self.column_gap,
+            children: self
+                .children
+                .into_iter()
+                .map(|(item, child)| (item, graphics::Vector2f::new(0.0, 0.0), child.to_actual_widget(id_maker)))
+                .collect(),
+            own_size: graphics::Vector2f::new(0.0, 0.0),
+            layout_cache: layout::LayoutCache::new(),
+            _phantom: PhantomData,
+            _private: (),
+        }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        let cache = &mut actual_widget.layout_cache;
+        cache.update(&mut actual_widget.rows, self.rows);
+        cache.update(&mut actual_widget.columns, self.columns);
+        cache.update(&mut actual_widget.row_gap, self.row_gap);
+        cache.update(&mut actual_widget.column_gap, self.column_gap);
+        if self.children.len() != actual_widget.children.len() {
+            cache.mark_dirty();
+        }
+
+        let ro_children = std::mem::take(&mut actual_widget.children);
+        let ro_children_infinite = ro_children.into_iter().map(Some).chain(std::iter::repeat_with(|| None));
+
+        let new_ro_children = self
+            .children
+            .into_iter()
+            .zip(ro_children_infinite)
+            .map(|((item, widget), ro)| match ro {
+                Some((old_item, offset, mut ro)) => {
+                    if old_item != item {
+                        cache.mark_dirty();
+                    }
+                    widget.update_actual_widget(&mut ro, id_maker);
+                    (item, offset, ro)
+                }
+                None => (item, graphics::Vector2f::new(0.0, 0.0), widget.to_actual_widget(id_maker)),
+            })
+            .collect();
+
+        actual_widget.children = new_ro_children;
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> GridActualWidget<Data, Child> {
+    // the sizes of the rows and of the columns, using measure to get the intrinsic size of each child
+    // columns are sized first so that children know how wide they are when the rows get sized
+    fn track_sizes(&self, available: graphics::Vector2f, measure: impl Fn(&Child, Direction, f32) -> f32) -> (Vec<f32>, Vec<f32>) {
+        let rows = with_implicit_tracks(&self.rows, self.children.iter().map(|(item, _, _)| item.row + item.row_span));
+        let columns = with_implicit_tracks(&self.columns, self.children.iter().map(|(item, _, _)| item.column + item.column_span));
+
+        let column_items: Vec<_> = self
+            .children
+            .iter()
+            .map(|(item, _, child)| (item.column, item.column_span, measure(child, Direction::Horizontal, f32::INFINITY)))
+            .collect();
+        let column_sizes = size_tracks(&columns, available.x, self.column_gap, &column_items);
+
+        let row_items: Vec<_> = self
+            .children
+            .iter()
+            .map(|(item, _, child)| {
+                let (_, cell_width) = cell_span(&column_sizes, self.column_gap, item.column, item.column_span);
+                (item.row, item.row_span, measure(child, Direction::Vertical, cell_width))
+            })
+            .collect();
+        let row_sizes = size_tracks(&rows, available.y, self.row_gap, &row_items);
+
+        (row_sizes, column_sizes)
+    }
+
+    fn intrinsic_size(&self, direction: Direction, cross_size: f32, measure: impl Fn(&Child, Direction, f32) -> f32) -> f32 {
+        let (row_sizes, column_sizes) = self.track_sizes(direction.make_vector_in_direction(f32::INFINITY, cross_size), measure);
+        match direction {
+            Direction::Horizontal => total_size(&column_sizes, self.column_gap),
+            Direction::Vertical => total_size(&row_sizes, self.row_gap),
+        }
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for GridActualWidget<Data, Child> {
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        let (row_sizes, column_sizes) =
+            self.track_sizes(sc.max, |child, direction, cross_size| child.max_intrinsic_size(graphics_context, direction, cross_size));
+
+        for (item, offset, child) in &mut self.children {
+            let (row_offset, cell_height) = cell_span(&row_sizes, self.row_gap, item.row, item.row_span);
+            let (column_offset, cell_width) = cell_span(&column_sizes, self.column_gap, item.column, item.column_span);
+
+            layout::layout_child(
+                child,
+                graphics_context,
+                layout::SizeConstraints {
+                    min: graphics::Vector2f::new(
+                        if item.horizontal_alignment == CellAlignment::Stretch { cell_width } else { 0.0 },
+                        if item.vertical_alignment == CellAlignment::Stretch { cell_height } else { 0.0 },
+                    ),
+                    max: graphics::Vector2f::new(cell_width, cell_height),
+                },
+            );
+            *offset = graphics::Vector2f::new(
+                column_offset + align(item.horizontal_alignment, cell_width - child.size().x),
+                row_offset + align(item.vertical_alignment, cell_height - child.size().y),
+            );
+        }
+
+        self.own_size = sc.clamp_size(graphics::Vector2f::new(total_size(&column_sizes, self.column_gap), total_size(&row_sizes, self.row_gap)));
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.children.iter().any(|(_, _, child)| child.needs_layout())
+    }
+
+    fn draw(
+        &self,
+        graphics_context: &graphics::GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
+    ) {
+        for (_, offset, child) in &self.children {
+            child.draw(graphics_context, target, top_left + *offset, hover, focus);
+        }
+    }
+
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_> {
+        Box::new(self.children.iter().flat_map(move |(_, offset, child)| child.find_hover(top_left + *offset, mouse)))
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        Box::new(self.children.iter().flat_map(|(_, _, child)| child.find_focusable()))
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.children.iter().find_map(|(_, _, child)| child.find_path(target))
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.children.iter().find_map(|(_, offset, child)| child.find_rect(top_left + *offset, query))
+    }
+
+    fn size(&self) -> graphics::Vector2f {
+        self.own_size
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.children.iter().find_map(|(_, offset, child)| child.baseline().map(|baseline| offset.y + baseline))
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.intrinsic_size(direction, cross_size, |child, direction, cross_size| child.min_intrinsic_size(graphics_context, direction, cross_size))
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.intrinsic_size(direction, cross_size, |child, direction, cross_size| child.max_intrinsic_size(graphics_context, direction, cross_size))
+    }
+
+    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: Event, ctx: &mut EventContext) {
+        for (_, offset, child) in &mut self.children {
+            child.dispatch_event(top_left + *offset, data, target, event, ctx);
+        }
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: Event, _: &mut EventContext) {}
+}
+
+fn with_implicit_tracks(tracks: &[Track], item_ends: impl Iterator<Item = usize>) -> Vec<Track> {
+    let track_count = item_ends.fold(tracks.len(), usize::max);
+    tracks.iter().copied().chain(std::iter::repeat(Track::Auto)).take(track_count).collect()
+}
+
+// items are (first track, number of tracks, size of the child along this axis)
+fn size_tracks(tracks: &[Track], available: f32, gap: f32, items: &[(usize, usize, f32)]) -> Vec<f32> {
+    let sized_by_content = |track: Track| match track {
+        Track::Fixed(_) => false,
+        Track::Fraction(_) => !available.is_finite(),
+        Track::Auto => true,
+    };
+    let mut sizes: Vec<f32> = tracks.iter().map(|track| if let Track::Fixed(size) = track { *size } else { 0.0 }).collect();
+
+    // children in a single track go first so that children spanning multiple tracks only grow them when the tracks are not already big enough
+    for &(start, _, size) in items.iter().filter(|(_, span, _)| *span == 1) {
+        if sized_by_content(tracks[start]) {
+            sizes[start] = sizes[start].max(size);
+        }
+    }
+    for &(start, span, size) in items.iter().filter(|(_, span, _)| *span > 1) {
+        let (_, current_size) = cell_span(&sizes, gap, start, span);
+        let growable: Vec<usize> = (start..start + span).filter(|index| sized_by_content(tracks[*index])).collect();
+        if size > current_size && !growable.is_empty() {
+            let extra = (size - current_size) / growable.len() as f32;
+            for index in growable {
+                sizes[index] += extra;
+            }
+        }
+    }
+
+    if available.is_finite() {
+        let free_space = (available - total_size(&sizes, gap)).max(0.0);
+        let total_fraction: f32 = tracks.iter().map(|track| if let Track::Fraction(fraction) = track { *fraction } else { 0.0 }).sum();
+        if total_fraction > 0.0 {
+            for (size, track) in sizes.iter_mut().zip(tracks) {
+                if let Track::Fraction(fraction) = track {
+                    *size = free_space * fraction / total_fraction;
+                }
+            }
+        }
+    }
+
+    sizes
+}
+
+// returns the offset of the first track and the size of all of the tracks together, including the gaps between them
+fn cell_span(sizes: &[f32], gap: f32, start: usize, span: usize) -> (f32, f32) {
+    let offset = sizes[..start].iter().sum::<f32>() + gap * start as f32;
+    (offset, total_size(&sizes[start..start + span], gap))
+}
+
+fn total_size(sizes: &[f32], gap: f32) -> f32 {
+    sizes.iter().sum::<f32>() + gap * sizes.len().saturating_sub(1) as f32
+}
+
+fn align(alignment: CellAlignment, free_space: f32) -> f32 {
+    match alignment {
+        CellAlignment::Start | CellAlignment::Stretch => 0.0,
+        CellAlignment::Center => free_space / 2.0,
+        CellAlignment::End => free_space,
+    }
+}
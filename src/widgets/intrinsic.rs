@@ -0,0 +1,164 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use crate::{
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
+    widgets::Widget,
+};
+
+// makes the child exactly as wide as it would like to be instead of as wide as it is allowed to be
+// this needs to measure the whole subtree before laying it out so it should not be used everywhere
+pub struct IntrinsicWidth<Data, Child: Widget<Data>> {
+    child: Child,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+// the same as IntrinsicWidth but for the height
+pub struct IntrinsicHeight<Data, Child: Widget<Data>> {
+    child: Child,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+pub struct IntrinsicActualWidget<Data, Child: ActualWidget<Data>> {
+    direction: Direction,
+    child: Child,
+    layout_cache: layout::LayoutCache,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+impl<Data, Child: Widget<Data>> IntrinsicWidth<Data, Child> {
+    pub fn new(child: Child) -> Self {
+        Self { child, _phantom: PhantomData }
+    }
+}
+
+impl<Data, Child: Widget<Data>> IntrinsicHeight<Data, Child> {
+    pub fn new(child: Child) -> Self {
+        Self { child, _phantom: PhantomData }
+    }
+}
+
+impl<Data, Child: Widget<Data>> Widget<Data> for IntrinsicWidth<Data, Child> {
+    type ActualWidget = IntrinsicActualWidget<Data, <Child as Widget<Data>>::ActualWidget>;
+
+    fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        IntrinsicActualWidget {
+            direction: Direction::Horizontal,
+            child: self.child.to_actual_widget(id_maker),
+            layout_cache: layout::LayoutCache::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        self.child.update_actual_widget(&mut actual_widget.child, id_maker);
+    }
+}
+
+impl<Data, Child: Widget<Data>> Widget<Data> for IntrinsicHeight<Data, Child> {
+    type ActualWidget = IntrinsicActualWidget<Data, <Child as Widget<Data>>::ActualWidget>;
+
+    fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        IntrinsicActualWidget {
+            direction: Direction::Vertical,
+            child: self.child.to_actual_widget(id_maker),
+            layout_cache: layout::LayoutCache::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        self.child.update_actual_widget(&mut actual_widget.child, id_maker);
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for IntrinsicActualWidget<Data, Child> {
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        let direction = self.direction;
+        let major_size = self
+            .child
+            .max_intrinsic_size(graphics_context, direction, direction.take_minor_component(sc.max))
+            .clamp(direction.take_major_component(sc.min), direction.take_major_component(sc.max));
+        layout::layout_child(
+            &mut self.child,
+            graphics_context,
+            layout::SizeConstraints {
+                min: direction.make_vector_in_direction(major_size, direction.take_minor_component(sc.min)),
+                max: direction.make_vector_in_direction(major_size, direction.take_minor_component(sc.max)),
+            },
+        );
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.child.needs_layout()
+    }
+
+    fn draw(
+        &self,
+        graphics_context: &graphics::GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
+    ) {
+        self.child.draw(graphics_context, target, top_left, hover, focus);
+    }
+
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_> {
+        self.child.find_hover(top_left, mouse)
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        self.child.find_focusable()
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.child.find_path(target)
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.child.find_rect(top_left, query)
+    }
+
+    fn size(&self) -> graphics::Vector2f {
+        self.child.size()
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.child.baseline()
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        if direction == self.direction {
+            // this never lays the child out any smaller than its max intrinsic size
+            self.child.max_intrinsic_size(graphics_context, direction, cross_size)
+        } else {
+            self.child.min_intrinsic_size(graphics_context, direction, cross_size)
+        }
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.max_intrinsic_size(graphics_context, direction, cross_size)
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        self.child.dispatch_event(top_left, data, target, event, ctx);
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::Event, _: &mut event::EventContext) {}
+}
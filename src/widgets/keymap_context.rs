@@ -0,0 +1,137 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use crate::{
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
+    widgets::Widget,
+};
+
+// makes the keymap bindings for this context apply while the focused widget is somewhere inside of the child
+pub struct KeymapContext<Data, Child: Widget<Data>> {
+    name: &'static str,
+    child: Child,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+pub struct KeymapContextActualWidget<Data, Child: ActualWidget<Data>> {
+    id: ActualWidgetId,
+    name: &'static str,
+    child: Child,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+    _private: (),
+}
+
+impl<Data, Child: Widget<Data>> KeymapContext<Data, Child> {
+    pub fn new(name: &'static str, child: Child) -> Self {
+        Self { name, child, _phantom: PhantomData }
+    }
+}
+
+impl<Data, Child: Widget<Data>> Widget<Data> for KeymapContext<Data, Child> {
+    type ActualWidget = KeymapContextActualWidget<Data, <Child as Widget<Data>>::ActualWidget>;
+
+    fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        KeymapContextActualWidget {
+            id: id_maker.next_id(),
+            name: self.name,
+            child: self.child.to_actual_widget(id_maker),
+            _phantom: PhantomData,
+            _private: (),
+        }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        actual_widget.name = self.name;
+        self.child.update_actual_widget(&mut actual_widget.child, id_maker);
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for KeymapContextActualWidget<Data, Child> {
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        layout::layout_child(&mut self.child, graphics_context, sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn draw(
+        &self,
+        graphics_context: &graphics::GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
+    ) {
+        self.child.draw(graphics_context, target, top_left, hover, focus);
+    }
+
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_> {
+        Box::new(
+            if graphics::FloatRect::from_vecs(top_left, self.size()).contains(mouse) { Some((self.id, true)) } else { None }
+                .into_iter()
+                .chain(self.child.find_hover(top_left, mouse)),
+        )
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        self.child.find_focusable()
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        if target == self.id {
+            Some(vec![self.id])
+        } else {
+            self.child.find_path(target).map(|path| std::iter::once(self.id).chain(path).collect())
+        }
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        if query.matches_id(self.id) {
+            Some(graphics::FloatRect::from_vecs(top_left, self.size()))
+        } else {
+            self.child.find_rect(top_left, query)
+        }
+    }
+
+    fn size(&self) -> graphics::Vector2f {
+        self.child.size()
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.child.baseline()
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.min_intrinsic_size(graphics_context, direction, cross_size)
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.max_intrinsic_size(graphics_context, direction, cross_size)
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        if target == self.id {
+            self.handle_event(top_left, data, event, ctx);
+        }
+
+        self.child.dispatch_event(top_left, data, target, event, ctx);
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, event: event::Event, ctx: &mut event::EventContext) {
+        // the capture phase goes from the outermost widget to the innermost one so the contexts get recorded in that order
+        if let (event::Event::KeyPressed { .. }, event::Phase::Capture | event::Phase::Target) = (event, ctx.phase()) {
+            ctx.enter_keymap_context(self.name);
+        }
+    }
+}
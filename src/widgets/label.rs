@@ -1,10 +1,11 @@
-use std::collections::HashSet;
+use std::{cell::Cell, collections::HashSet};
 
 use sfml::graphics::{Font, Transformable};
 
 use crate::{
-    actual_widget::{util, ActualWidget, ActualWidgetId, ActualWidgetIdMaker},
-    event, graphics, layout,
+    actual_widget::{util, ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
     widgets::Widget,
 };
 
@@ -20,6 +21,9 @@ pub struct LabelActualWidget<GetFont: Fn(&graphics::Fonts) -> &Font> {
     get_font: GetFont,
     font_size: u32,
     size: graphics::Vector2f,
+    // measuring the text means making an sfml Text, so it is only done again when the text, the font or the font size changes
+    text_size: Cell<Option<graphics::Vector2f>>,
+    layout_cache: layout::LayoutCache,
     _private: (),
 }
 
@@ -39,22 +43,46 @@ impl<GetFont: Fn(&graphics::Fonts) -> &Font, Data> Widget<Data> for Label<GetFon
             get_font: self.get_font,
             font_size: self.font_size,
             size: graphics::Vector2f::new(0.0, 0.0),
+            text_size: Cell::new(None),
+            layout_cache: layout::LayoutCache::new(),
             _private: (),
         }
     }
 
     fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, _: &mut ActualWidgetIdMaker) {
-        actual_widget.text = self.text;
-        actual_widget.get_font = self.get_font;
-        actual_widget.font_size = self.font_size;
+        actual_widget.layout_cache.update_fn(&mut actual_widget.get_font, self.get_font);
+        actual_widget.layout_cache.update(&mut actual_widget.text, self.text);
+        actual_widget.layout_cache.update(&mut actual_widget.font_size, self.font_size);
+        if actual_widget.layout_cache.is_dirty() {
+            actual_widget.text_size.set(None);
+        }
     }
 }
 
-impl<GetFont: Fn(&graphics::Fonts) -> &Font, Data> ActualWidget<Data> for LabelActualWidget<GetFont> {
-    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+impl<GetFont: Fn(&graphics::Fonts) -> &Font> LabelActualWidget<GetFont> {
+    fn text_size(&self, graphics_context: &graphics::GraphicsContext) -> graphics::Vector2f {
+        if let Some(text_size) = self.text_size.get() {
+            return text_size;
+        }
         let text = graphics::Text::new(&self.text, (self.get_font)(&graphics_context.fonts), self.font_size);
         let global_bounds = text.global_bounds();
-        self.size = sc.clamp_size(graphics::Vector2f::new(global_bounds.left + global_bounds.width, global_bounds.top + global_bounds.height));
+        let text_size = graphics::Vector2f::new(global_bounds.left + global_bounds.width, global_bounds.top + global_bounds.height);
+        self.text_size.set(Some(text_size));
+        text_size
+    }
+}
+
+impl<GetFont: Fn(&graphics::Fonts) -> &Font, Data> ActualWidget<Data> for LabelActualWidget<GetFont> {
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.layout_cache.is_dirty() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        self.size = sc.clamp_size(self.text_size(graphics_context));
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty()
     }
 
     fn draw(
@@ -63,6 +91,7 @@ impl<GetFont: Fn(&graphics::Fonts) -> &Font, Data> ActualWidget<Data> for LabelA
         target: &mut dyn graphics::RenderTarget,
         top_left: graphics::Vector2f,
         _: &HashSet<ActualWidgetId>,
+        _: Option<ActualWidgetId>,
     ) {
         // TODO: deal with overflow better than by clipping
         // TODO: also fix messy rendering that is caused by clipping
@@ -78,16 +107,56 @@ impl<GetFont: Fn(&graphics::Fonts) -> &Font, Data> ActualWidget<Data> for LabelA
         Box::new(if graphics::FloatRect::from_vecs(top_left, self.size).contains(mouse) { Some((self.id, true)) } else { None }.into_iter())
     }
 
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        if target == self.id {
+            Some(vec![self.id])
+        } else {
+            None
+        }
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        if query.matches_id(self.id) {
+            Some(graphics::FloatRect::from_vecs(top_left, self.size))
+        } else {
+            None
+        }
+    }
+
     fn size(&self) -> graphics::Vector2f {
         self.size
     }
 
-    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: event::TargetedEvent) {
+    // sfml puts the baseline of the first line of a text one character size below the position it is drawn at
+    fn baseline(&self) -> Option<f32> {
+        Some(self.font_size as f32)
+    }
+
+    // labels never wrap so there is only one size they can be
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, _: f32) -> f32 {
+        direction.take_major_component(self.text_size(graphics_context))
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, _: f32) -> f32 {
+        direction.take_major_component(self.text_size(graphics_context))
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
         if target == self.id {
-            self.targeted_event(top_left, data, event);
+            self.handle_event(top_left, data, event, ctx);
         }
     }
 
-    fn targeted_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::TargetedEvent) {}
-    fn general_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::GeneralEvent) {}
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::Event, _: &mut event::EventContext) {}
 }
@@ -0,0 +1,146 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use crate::{
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
+    widgets::Widget,
+};
+
+// builds the child from the constraints that this widget gets laid out with, for layouts that change depending on how much space there is
+// the child gets rebuilt whenever the constraints change, and the new child is reconciled with the old one so the state inside it is kept
+pub struct LayoutBuilder<Data, Child: Widget<Data>, Build: Fn(layout::SizeConstraints) -> Child> {
+    build: Build,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+pub struct LayoutBuilderActualWidget<Data, Child: Widget<Data>, Build: Fn(layout::SizeConstraints) -> Child> {
+    build: Build,
+    // the constraints that the child was last built with
+    built_for: Option<layout::SizeConstraints>,
+    // None until the first layout because there are no constraints to build it with before that
+    child: Option<<Child as Widget<Data>>::ActualWidget>,
+    layout_cache: layout::LayoutCache,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+impl<Data, Child: Widget<Data>, Build: Fn(layout::SizeConstraints) -> Child> LayoutBuilder<Data, Child, Build> {
+    pub fn new(build: Build) -> Self {
+        Self { build, _phantom: PhantomData }
+    }
+}
+
+impl<Data, Child: Widget<Data>, Build: Fn(layout::SizeConstraints) -> Child> Widget<Data> for LayoutBuilder<Data, Child, Build> {
+    type ActualWidget = LayoutBuilderActualWidget<Data, Child, Build>;
+
+    fn to_actual_widget(self, _: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        LayoutBuilderActualWidget { build: self.build, built_for: None, child: None, layout_cache: layout::LayoutCache::new(), _phantom: PhantomData }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        actual_widget.build = self.build;
+        // the new builder could have captured a different model so rebuild now instead of waiting for the constraints to change
+        if let Some(sc) = actual_widget.built_for {
+            actual_widget.rebuild(sc, id_maker);
+        }
+    }
+}
+
+impl<Data, Child: Widget<Data>, Build: Fn(layout::SizeConstraints) -> Child> LayoutBuilderActualWidget<Data, Child, Build> {
+    fn rebuild(&mut self, sc: layout::SizeConstraints, id_maker: &mut ActualWidgetIdMaker) {
+        let widget = (self.build)(sc);
+        match &mut self.child {
+            Some(child) => widget.update_actual_widget(child, id_maker),
+            None => self.child = Some(widget.to_actual_widget(id_maker)),
+        }
+        self.built_for = Some(sc);
+    }
+}
+
+impl<Data, Child: Widget<Data>, Build: Fn(layout::SizeConstraints) -> Child> ActualWidget<Data> for LayoutBuilderActualWidget<Data, Child, Build> {
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        if self.built_for != Some(sc) {
+            self.rebuild(sc, &mut ActualWidgetIdMaker::new());
+        }
+        if let Some(child) = &mut self.child {
+            layout::layout_child(child, graphics_context, sc);
+        }
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.child.as_ref().is_some_and(|child| child.needs_layout())
+    }
+
+    fn draw(
+        &self,
+        graphics_context: &graphics::GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
+    ) {
+        if let Some(child) = &self.child {
+            child.draw(graphics_context, target, top_left, hover, focus);
+        }
+    }
+
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_> {
+        match &self.child {
+            Some(child) => child.find_hover(top_left, mouse),
+            None => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        match &self.child {
+            Some(child) => child.find_focusable(),
+            None => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.child.as_ref().and_then(|child| child.find_path(target))
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.child.as_ref().and_then(|child| child.find_rect(top_left, query))
+    }
+
+    fn size(&self) -> graphics::Vector2f {
+        self.child.as_ref().map_or(graphics::Vector2f::new(0.0, 0.0), |child| child.size())
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.child.as_ref().and_then(|child| child.baseline())
+    }
+
+    // the child that would be built for other constraints is unknown, so these go by the child that was built last
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.as_ref().map_or(0.0, |child| child.min_intrinsic_size(graphics_context, direction, cross_size))
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.as_ref().map_or(0.0, |child| child.max_intrinsic_size(graphics_context, direction, cross_size))
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        if let Some(child) = &mut self.child {
+            child.dispatch_event(top_left, data, target, event, ctx);
+        }
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::Event, _: &mut event::EventContext) {}
+}
@@ -1,8 +1,9 @@
 use std::{collections::HashSet, marker::PhantomData};
 
 use crate::{
-    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker},
-    event, graphics, layout,
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
     widgets::Widget,
 };
 
@@ -16,6 +17,7 @@ pub struct MinSize<Data, Child: Widget<Data>> {
 pub struct MinSizeActualWidget<Data, Child: ActualWidget<Data>> {
     child: Child,
     min_size: graphics::Vector2f,
+    layout_cache: layout::LayoutCache,
 
     _phantom: PhantomData<fn(&mut Data)>,
 }
@@ -30,19 +32,32 @@ impl<Data, Child: Widget<Data>> Widget<Data> for MinSize<Data, Child> {
     type ActualWidget = MinSizeActualWidget<Data, <Child as Widget<Data>>::ActualWidget>;
 
     fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
-        MinSizeActualWidget { child: self.child.to_actual_widget(id_maker), min_size: self.min_size, _phantom: PhantomData }
+        MinSizeActualWidget {
+            child: self.child.to_actual_widget(id_maker),
+            min_size: self.min_size,
+            layout_cache: layout::LayoutCache::new(),
+            _phantom: PhantomData,
+        }
     }
 
     fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
-        actual_widget.min_size = self.min_size;
+        actual_widget.layout_cache.update(&mut actual_widget.min_size, self.min_size);
         self.child.update_actual_widget(&mut actual_widget.child, id_maker);
     }
 }
 
 impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for MinSizeActualWidget<Data, Child> {
     fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
         let size = sc.clamp_size(self.min_size);
-        self.child.layout(graphics_context, layout::SizeConstraints { min: size, max: sc.max });
+        layout::layout_child(&mut self.child, graphics_context, layout::SizeConstraints { min: size, max: sc.max });
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.child.needs_layout()
     }
 
     fn draw(
@@ -51,24 +66,55 @@ impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for MinSizeActualWidget
         target: &mut dyn graphics::RenderTarget,
         top_left: graphics::Vector2f,
         hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
     ) {
-        self.child.draw(graphics_context, target, top_left, hover);
+        self.child.draw(graphics_context, target, top_left, hover, focus);
     }
 
     fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<(dyn Iterator<Item = (ActualWidgetId, bool)> + '_)> {
         self.child.find_hover(top_left, mouse)
     }
 
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        self.child.find_focusable()
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.child.find_path(target)
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.child.find_rect(top_left, query)
+    }
+
     fn size(&self) -> graphics::Vector2f {
         self.child.size()
     }
 
-    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: event::TargetedEvent) {
-        self.child.dispatch_event(top_left, data, target, event);
+    fn baseline(&self) -> Option<f32> {
+        self.child.baseline()
     }
 
-    fn targeted_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::TargetedEvent) {}
-    fn general_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, event: event::GeneralEvent) {
-        self.child.general_event(top_left, data, event);
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        let cross_size = cross_size.max(direction.take_minor_component(self.min_size));
+        self.child.min_intrinsic_size(graphics_context, direction, cross_size).max(direction.take_major_component(self.min_size))
     }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        let cross_size = cross_size.max(direction.take_minor_component(self.min_size));
+        self.child.max_intrinsic_size(graphics_context, direction, cross_size).max(direction.take_major_component(self.min_size))
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        self.child.dispatch_event(top_left, data, target, event, ctx);
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::Event, _: &mut event::EventContext) {}
 }
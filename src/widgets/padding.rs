@@ -1,8 +1,9 @@
 use std::{collections::HashSet, marker::PhantomData};
 
 use crate::{
-    actual_widget::{animated::Animated, ActualWidget, ActualWidgetId, ActualWidgetIdMaker},
-    event, graphics, layout,
+    actual_widget::{animated::Animated, ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
     widgets::Widget,
 };
 
@@ -23,6 +24,9 @@ pub struct PaddingActualWidget<Data, Child: ActualWidget<Data>> {
     top: Animated<f32>,
     right: Animated<f32>,
     bottom: Animated<f32>,
+    // where the child goes, which is less than the left and top padding if the padding had to be cut down to fit
+    child_offset: graphics::Vector2f,
+    layout_cache: layout::LayoutCache,
 
     _phantom: PhantomData<fn(&mut Data)>,
 }
@@ -47,6 +51,8 @@ impl<Data, Child: Widget<Data>> Widget<Data> for Padding<Data, Child> {
             top: Animated::new(self.top),
             right: Animated::new(self.right),
             bottom: Animated::new(self.bottom),
+            child_offset: graphics::Vector2f::new(0.0, 0.0),
+            layout_cache: layout::LayoutCache::new(),
             _phantom: PhantomData,
         }
     }
@@ -60,15 +66,35 @@ impl<Data, Child: Widget<Data>> Widget<Data> for Padding<Data, Child> {
     }
 }
 
+impl<Data, Child: ActualWidget<Data>> PaddingActualWidget<Data, Child> {
+    fn total_padding(&self) -> graphics::Vector2f {
+        graphics::Vector2f::new(self.left.get_lerped() + self.right.get_lerped(), self.top.get_lerped() + self.bottom.get_lerped())
+    }
+
+    fn is_animating(&self) -> bool {
+        self.left.is_animating() || self.top.is_animating() || self.right.is_animating() || self.bottom.is_animating()
+    }
+}
+
 impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for PaddingActualWidget<Data, Child> {
     fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
-        let shrunk_sc =
-            sc.shrink(graphics::Vector2f::new(self.left.get_lerped() + self.right.get_lerped(), self.top.get_lerped() + self.bottom.get_lerped()));
-        self.child.layout(graphics_context, shrunk_sc);
-        self.size = sc.clamp_size(
-            self.child.size()
-                + graphics::Vector2f::new(self.left.get_lerped() + self.right.get_lerped(), self.top.get_lerped() + self.bottom.get_lerped()),
-        );
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        // padding that is too big to fit gets cut down, keeping the same proportion between the two sides
+        let total_padding = self.total_padding();
+        let fit = |padding: f32, max: f32| if padding > max { max / padding } else { 1.0 };
+        let fit = graphics::Vector2f::new(fit(total_padding.x, sc.max.x), fit(total_padding.y, sc.max.y));
+        let used_padding = graphics::Vector2f::new(total_padding.x * fit.x, total_padding.y * fit.y);
+        self.child_offset = graphics::Vector2f::new(self.left.get_lerped() * fit.x, self.top.get_lerped() * fit.y);
+
+        layout::layout_child(&mut self.child, graphics_context, sc.shrink(used_padding));
+        self.size = sc.clamp_size(self.child.size() + used_padding);
+        self.layout_cache.laid_out_animating(sc, self.is_animating());
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty_or_animating(self.is_animating()) || self.child.needs_layout()
     }
 
     fn draw(
@@ -77,25 +103,57 @@ impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for PaddingActualWidget
         target: &mut dyn graphics::RenderTarget,
         top_left: graphics::Vector2f,
         hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
     ) {
-        // TODO: calculate offset better in order to account for cases where the padding must be cut off because it would be too big to fit in the size constraints
-        self.child.draw(graphics_context, target, top_left + graphics::Vector2f::new(self.left.get_lerped(), self.top.get_lerped()), hover);
+        self.child.draw(graphics_context, target, top_left + self.child_offset, hover, focus);
     }
 
     fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<(dyn Iterator<Item = (ActualWidgetId, bool)> + '_)> {
-        self.child.find_hover(top_left + graphics::Vector2f::new(self.left.get_lerped(), self.top.get_lerped()), mouse)
+        self.child.find_hover(top_left + self.child_offset, mouse)
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        self.child.find_focusable()
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.child.find_path(target)
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.child.find_rect(top_left + self.child_offset, query)
     }
 
     fn size(&self) -> graphics::Vector2f {
         self.size
     }
 
-    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: event::TargetedEvent) {
-        self.child.dispatch_event(top_left + graphics::Vector2f::new(self.left.get_lerped(), self.top.get_lerped()), data, target, event);
+    fn baseline(&self) -> Option<f32> {
+        self.child.baseline().map(|baseline| self.child_offset.y + baseline)
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        let padding = self.total_padding();
+        self.child.min_intrinsic_size(graphics_context, direction, (cross_size - direction.take_minor_component(padding)).max(0.0))
+            + direction.take_major_component(padding)
     }
 
-    fn targeted_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::TargetedEvent) {}
-    fn general_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, event: event::GeneralEvent) {
-        self.child.general_event(top_left, data, event);
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        let padding = self.total_padding();
+        self.child.max_intrinsic_size(graphics_context, direction, (cross_size - direction.take_minor_component(padding)).max(0.0))
+            + direction.take_major_component(padding)
     }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        self.child.dispatch_event(top_left + self.child_offset, data, target, event, ctx);
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::Event, _: &mut event::EventContext) {}
 }
@@ -1,15 +1,26 @@
-// TODO: this should probably be removed when i figure out a better event dispatch system
 use std::{collections::HashSet, marker::PhantomData};
 
 use crate::{
-    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker},
-    event, graphics, layout,
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
     widgets::Widget,
 };
 
+// which key events the callback gets called for
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum KeyTrigger {
+    // only when the key is first pressed down
+    Press,
+    // when the key is pressed down and every time it auto repeats while held down
+    PressAndRepeat,
+    Release,
+}
+
 pub struct RespondsToKeyboard<Data, Child: Widget<Data>, Callback: Fn(&mut Data)> {
-    key: sfml::window::Key,
-    on_press: Callback,
+    chord: event::KeyChord,
+    trigger: KeyTrigger,
+    callback: Callback,
     child: Child,
 
     _phantom: PhantomData<fn(&mut Data)>,
@@ -17,8 +28,9 @@ pub struct RespondsToKeyboard<Data, Child: Widget<Data>, Callback: Fn(&mut Data)
 
 pub struct RespondsToKeyboardActualWidget<Data, Child: ActualWidget<Data>, Callback: Fn(&mut Data)> {
     id: ActualWidgetId,
-    key: sfml::window::Key,
-    on_press: Callback,
+    chord: event::KeyChord,
+    trigger: KeyTrigger,
+    callback: Callback,
     child: Child,
 
     _phantom: PhantomData<fn(&mut Data)>,
@@ -26,8 +38,11 @@ pub struct RespondsToKeyboardActualWidget<Data, Child: ActualWidget<Data>, Callb
 }
 
 impl<Data, Child: Widget<Data>, Callback: Fn(&mut Data)> RespondsToKeyboard<Data, Child, Callback> {
-    pub fn new(key: sfml::window::Key, on_press: Callback, child: Child) -> Self {
-        Self { key, on_press, child, _phantom: PhantomData }
+    pub fn new(chord: event::KeyChord, callback: Callback, child: Child) -> Self {
+        Self::new_with_trigger(chord, KeyTrigger::PressAndRepeat, callback, child)
+    }
+    pub fn new_with_trigger(chord: event::KeyChord, trigger: KeyTrigger, callback: Callback, child: Child) -> Self {
+        Self { chord, trigger, callback, child, _phantom: PhantomData }
     }
 }
 
@@ -37,8 +52,9 @@ impl<Data, Child: Widget<Data>, Callback: Fn(&mut Data)> Widget<Data> for Respon
     fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
         RespondsToKeyboardActualWidget {
             id: id_maker.next_id(),
-            key: self.key,
-            on_press: self.on_press,
+            chord: self.chord,
+            trigger: self.trigger,
+            callback: self.callback,
             child: self.child.to_actual_widget(id_maker),
             _phantom: PhantomData,
             _private: (),
@@ -46,15 +62,20 @@ impl<Data, Child: Widget<Data>, Callback: Fn(&mut Data)> Widget<Data> for Respon
     }
 
     fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
-        actual_widget.key = self.key;
-        actual_widget.on_press = self.on_press;
+        actual_widget.chord = self.chord;
+        actual_widget.trigger = self.trigger;
+        actual_widget.callback = self.callback;
         self.child.update_actual_widget(&mut actual_widget.child, id_maker);
     }
 }
 
 impl<Data, Child: ActualWidget<Data>, Callback: Fn(&mut Data)> ActualWidget<Data> for RespondsToKeyboardActualWidget<Data, Child, Callback> {
     fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
-        self.child.layout(graphics_context, sc);
+        layout::layout_child(&mut self.child, graphics_context, sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
     }
 
     fn draw(
@@ -63,11 +84,12 @@ impl<Data, Child: ActualWidget<Data>, Callback: Fn(&mut Data)> ActualWidget<Data
         target: &mut dyn graphics::RenderTarget,
         top_left: graphics::Vector2f,
         hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
     ) {
-        self.child.draw(graphics_context, target, top_left, hover);
+        self.child.draw(graphics_context, target, top_left, hover, focus);
     }
 
-    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<(dyn Iterator<Item = (ActualWidgetId, bool)> + '_)> {
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_> {
         Box::new(
             if graphics::FloatRect::from_vecs(top_left, self.size()).contains(mouse) { Some((self.id, true)) } else { None }
                 .into_iter()
@@ -75,32 +97,84 @@ impl<Data, Child: ActualWidget<Data>, Callback: Fn(&mut Data)> ActualWidget<Data
         )
     }
 
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        self.child.find_focusable()
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        if target == self.id {
+            Some(vec![self.id])
+        } else {
+            self.child.find_path(target).map(|path| std::iter::once(self.id).chain(path).collect())
+        }
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        if query.matches_id(self.id) {
+            Some(graphics::FloatRect::from_vecs(top_left, self.size()))
+        } else {
+            self.child.find_rect(top_left, query)
+        }
+    }
+
     fn size(&self) -> graphics::Vector2f {
         self.child.size()
     }
 
-    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: event::TargetedEvent) {
+    fn baseline(&self) -> Option<f32> {
+        self.child.baseline()
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.min_intrinsic_size(graphics_context, direction, cross_size)
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.max_intrinsic_size(graphics_context, direction, cross_size)
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
         if target == self.id {
-            self.targeted_event(top_left, data, event);
+            self.handle_event(top_left, data, event, ctx);
         }
 
-        self.child.dispatch_event(top_left, data, target, event);
+        self.child.dispatch_event(top_left, data, target, event, ctx);
     }
 
-    fn targeted_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: event::TargetedEvent) {}
-    fn general_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, event: event::GeneralEvent) {
-        match event {
-            event::GeneralEvent::MouseMoved(_) => {}
-            event::GeneralEvent::LeftMouseUp => {}
-            event::GeneralEvent::RightMouseUp => {}
-            event::GeneralEvent::KeyPressed { code, .. } => {
-                if code == self.key {
-                    // TODO: modifier keys?
-                    (self.on_press)(data);
-                }
-            }
+    fn handle_event(&mut self, _: graphics::Vector2f, data: &mut Data, event: event::Event, ctx: &mut event::EventContext) {
+        if ctx.phase() == event::Phase::Capture {
+            return;
         }
 
-        self.child.general_event(top_left, data, event);
+        let triggered = match event {
+            event::Event::KeyPressed { code, scan, modifiers, repeat } => {
+                let trigger_matches = match self.trigger {
+                    KeyTrigger::Press => !repeat,
+                    KeyTrigger::PressAndRepeat => true,
+                    KeyTrigger::Release => false,
+                };
+                trigger_matches && self.chord.matches(code, scan, modifiers)
+            }
+            event::Event::KeyReleased { code, scan, modifiers } => self.trigger == KeyTrigger::Release && self.chord.matches(code, scan, modifiers),
+            event::Event::MouseDown { .. }
+            | event::Event::MouseMoved(_)
+            | event::Event::MouseUp { .. }
+            | event::Event::TextEntered(_)
+            | event::Event::MouseWheelScrolled { .. }
+            | event::Event::PointerEnter
+            | event::Event::PointerLeave => false,
+        };
+
+        if triggered {
+            (self.callback)(data);
+            ctx.set_handled();
+        }
     }
 }
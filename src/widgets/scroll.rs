@@ -0,0 +1,285 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use sfml::graphics::Shape;
+
+use crate::{
+    actual_widget::{animated::Animated, util, ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
+    widgets::Widget,
+};
+
+const SCROLLBAR_THICKNESS: f32 = 8.0;
+const MIN_THUMB_LENGTH: f32 = 16.0;
+const WHEEL_SCROLL_AMOUNT: f32 = 40.0;
+
+pub struct Scroll<Data, Child: Widget<Data>> {
+    direction: Direction,
+    child: Child,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+pub struct ScrollActualWidget<Data, Child: ActualWidget<Data>> {
+    id: ActualWidgetId,
+    direction: Direction,
+    child: Child,
+
+    offset: Animated<f32>,
+    // the offset when the drag started
+    dragging_thumb: Option<f32>,
+    size: graphics::Vector2f,
+    layout_cache: layout::LayoutCache,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+    _private: (),
+}
+
+impl<Data, Child: Widget<Data>> Scroll<Data, Child> {
+    pub fn new(direction: Direction, child: Child) -> Self {
+        Self { direction, child, _phantom: PhantomData }
+    }
+    pub fn new_horizontal(child: Child) -> Self {
+        Self::new(Direction::Horizontal, child)
+    }
+    pub fn new_vertical(child: Child) -> Self {
+        Self::new(Direction::Vertical, child)
+    }
+}
+
+impl<Data, Child: Widget<Data>> Widget<Data> for Scroll<Data, Child> {
+    type ActualWidget = ScrollActualWidget<Data, <Child as Widget<Data>>::ActualWidget>;
+
+    fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        ScrollActualWidget {
+            id: id_maker.next_id(),
+            direction: self.direction,
+            child: self.child.to_actual_widget(id_maker),
+            offset: Animated::new(0.0),
+            dragging_thumb: None,
+            size: graphics::Vector2f::new(0.0, 0.0),
+            layout_cache: layout::LayoutCache::new(),
+            _phantom: PhantomData,
+            _private: (),
+        }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        actual_widget.layout_cache.update(&mut actual_widget.direction, self.direction);
+        self.child.update_actual_widget(&mut actual_widget.child, id_maker);
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> ScrollActualWidget<Data, Child> {
+    fn viewport_length(&self) -> f32 {
+        self.direction.take_major_component(self.size)
+    }
+
+    fn content_length(&self) -> f32 {
+        self.direction.take_major_component(self.child.size())
+    }
+
+    fn max_offset(&self) -> f32 {
+        (self.content_length() - self.viewport_length()).max(0.0)
+    }
+
+    fn child_top_left(&self, top_left: graphics::Vector2f) -> graphics::Vector2f {
+        top_left - self.direction.make_vector_in_direction(self.offset.get_lerped(), 0.0)
+    }
+
+    // the rect of the scrollbar thumb, or None if everything fits and there is nothing to scroll
+    fn thumb_rect(&self, top_left: graphics::Vector2f) -> Option<graphics::FloatRect> {
+        if self.max_offset() <= 0.0 {
+            return None;
+        }
+
+        let track_length = self.viewport_length();
+        let thumb_length = (track_length * self.viewport_length() / self.content_length()).max(MIN_THUMB_LENGTH).min(track_length);
+        let thumb_start = self.offset.get_lerped() / self.max_offset() * (track_length - thumb_length);
+        let minor_start = self.direction.take_minor_component(self.size) - SCROLLBAR_THICKNESS;
+
+        Some(graphics::FloatRect::from_vecs(
+            top_left + self.direction.make_vector_in_direction(thumb_start, minor_start),
+            self.direction.make_vector_in_direction(thumb_length, SCROLLBAR_THICKNESS),
+        ))
+    }
+
+    fn scroll_to(&mut self, offset: f32, animate: bool) {
+        let offset = offset.clamp(0.0, self.max_offset());
+        if animate {
+            self.offset.set(offset);
+        } else {
+            self.offset.set_immediately(offset);
+        }
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for ScrollActualWidget<Data, Child> {
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        layout::layout_child(&mut self.child, graphics_context, sc.unbounded_along(self.direction));
+        self.size = sc.clamp_size(self.child.size());
+
+        // the content might have shrunk so that the current offset is scrolled past the end
+        if *self.offset.get_current() > self.max_offset() {
+            self.offset.set(self.max_offset());
+        }
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.child.needs_layout()
+    }
+
+    fn draw(
+        &self,
+        graphics_context: &graphics::GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
+    ) {
+        util::clip(graphics_context, target, graphics::FloatRect::from_vecs(top_left, self.size), |target, top_left| {
+            self.child.draw(graphics_context, target, self.child_top_left(top_left), hover, focus);
+        });
+
+        if let Some(thumb_rect) = self.thumb_rect(top_left) {
+            let mut thumb = graphics::RectangleShape::from_rect(thumb_rect);
+            // TODO: pick better colors for these
+            thumb.set_fill_color(if self.dragging_thumb.is_some() || hover.contains(&self.id) {
+                graphics::Color::rgba(255, 255, 255, 150)
+            } else {
+                graphics::Color::rgba(255, 255, 255, 80)
+            });
+            target.draw(&thumb);
+        }
+    }
+
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_> {
+        if graphics::FloatRect::from_vecs(top_left, self.size).contains(mouse) {
+            let on_thumb = self.thumb_rect(top_left).is_some_and(|thumb_rect| thumb_rect.contains(mouse));
+            // the scrollbar covers up the child
+            Box::new(
+                std::iter::once((self.id, !on_thumb))
+                    .chain(if on_thumb { None } else { Some(self.child.find_hover(self.child_top_left(top_left), mouse)) }.into_iter().flatten()),
+            )
+        } else {
+            // the parts of the child that are scrolled out of view cannot be hovered
+            Box::new(std::iter::empty())
+        }
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        self.child.find_focusable()
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        if target == self.id {
+            Some(vec![self.id])
+        } else {
+            self.child.find_path(target).map(|path| std::iter::once(self.id).chain(path).collect())
+        }
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        if query.matches_id(self.id) {
+            Some(graphics::FloatRect::from_vecs(top_left, self.size()))
+        } else {
+            self.child.find_rect(self.child_top_left(top_left), query)
+        }
+    }
+
+    fn size(&self) -> graphics::Vector2f {
+        self.size
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.child.baseline().map(|baseline| self.child_top_left(graphics::Vector2f::new(0.0, 0.0)).y + baseline)
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        if direction == self.direction {
+            // everything past this can be scrolled to
+            0.0
+        } else {
+            self.child.min_intrinsic_size(graphics_context, direction, cross_size)
+        }
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.child.max_intrinsic_size(graphics_context, direction, cross_size)
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        if target == self.id {
+            self.handle_event(top_left, data, event, ctx);
+        }
+
+        let child_top_left = self.child_top_left(top_left);
+        self.child.dispatch_event(child_top_left, data, target, event, ctx);
+    }
+
+    fn handle_event(&mut self, top_left: graphics::Vector2f, _: &mut Data, event: event::Event, ctx: &mut event::EventContext) {
+        if ctx.phase() == event::Phase::Capture {
+            return;
+        }
+
+        match event {
+            event::Event::MouseDown { button: sfml::window::mouse::Button::Left, position: mouse_position, .. } => {
+                if self.thumb_rect(top_left).is_some_and(|thumb_rect| thumb_rect.contains(mouse_position)) {
+                    self.dragging_thumb = Some(*self.offset.get_current());
+                    ctx.capture_pointer();
+                    ctx.set_handled();
+                }
+            }
+            event::Event::MouseDown { .. } => {}
+            event::Event::MouseMoved(_) => {
+                if let (Some(drag_start_offset), Some(drag)) = (self.dragging_thumb, ctx.drag()) {
+                    let track_length = self.viewport_length();
+                    let thumb_length = self.thumb_rect(top_left).map_or(track_length, |thumb_rect| {
+                        self.direction.take_major_component(graphics::Vector2f::new(thumb_rect.width, thumb_rect.height))
+                    });
+                    // moving the thumb all the way across the track scrolls all the way across the content
+                    let offset_per_pixel = if track_length > thumb_length { self.max_offset() / (track_length - thumb_length) } else { 0.0 };
+                    let mouse_delta = self.direction.take_major_component(drag.position - drag.start);
+                    self.scroll_to(drag_start_offset + mouse_delta * offset_per_pixel, false);
+                    ctx.set_handled();
+                }
+            }
+            event::Event::MouseUp { button: sfml::window::mouse::Button::Left, .. } => {
+                if self.dragging_thumb.is_some() {
+                    self.dragging_thumb = None;
+                    ctx.set_handled();
+                }
+            }
+            event::Event::MouseUp { .. } => {}
+            event::Event::KeyPressed { .. } => {}
+            event::Event::KeyReleased { .. } => {}
+            event::Event::TextEntered(_) => {}
+            event::Event::PointerEnter => {}
+            event::Event::PointerLeave => {}
+            event::Event::MouseWheelScrolled { wheel, delta, position: _ } => {
+                let scrolls_this_direction = match (self.direction, wheel) {
+                    (Direction::Vertical, sfml::window::mouse::Wheel::VerticalWheel) => true,
+                    (Direction::Vertical, sfml::window::mouse::Wheel::HorizontalWheel) => false,
+                    // most mice only have a vertical wheel so horizontal scroll views respond to it too
+                    (Direction::Horizontal, _) => true,
+                };
+                if scrolls_this_direction {
+                    // positive delta is up / left
+                    self.scroll_to(self.offset.get_current() - delta * WHEEL_SCROLL_AMOUNT, true);
+                    ctx.set_handled();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use sfml::{graphics::Shape, window::Key};
+
+use crate::{
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
+    widgets::Widget,
+};
+
+const DIVIDER_THICKNESS: f32 = 6.0;
+// how far the arrow keys move the divider when it is focused
+const KEYBOARD_STEP: f32 = 10.0;
+const KEYBOARD_STEP_SHIFT: f32 = 50.0;
+// the ratio that a collapsed split goes back to if it did not have one from before it was collapsed
+const DEFAULT_RATIO: f32 = 0.5;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Side {
+    // the left side of a horizontal split or the top side of a vertical split
+    #[default]
+    First,
+    Second,
+}
+
+// shows two children next to each other with a divider between them that can be dragged to change how much space each child gets
+// the ratio is how much of the space (not counting the divider) goes to the first child, and lives in the model so that it can be saved
+pub struct Split<Data, First: Widget<Data>, Second: Widget<Data>, GetRatio: Fn(&mut Data) -> &mut f32> {
+    direction: Direction,
+    ratio: f32,
+    get_ratio: GetRatio,
+    first: First,
+    second: Second,
+    first_limits: (f32, f32),
+    second_limits: (f32, f32),
+    collapsible_side: Side,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+
+pub struct SplitActualWidget<Data, First: ActualWidget<Data>, Second: ActualWidget<Data>, GetRatio: Fn(&mut Data) -> &mut f32> {
+    // the id of the divider, which is the only part of this widget that can be hovered or focused
+    id: ActualWidgetId,
+    direction: Direction,
+    ratio: f32,
+    get_ratio: GetRatio,
+    first: First,
+    second: Second,
+    first_limits: (f32, f32),
+    second_limits: (f32, f32),
+    collapsible_side: Side,
+
+    // the space shared between both children, calculated during layout
+    content_length: f32,
+    first_length: f32,
+    size: graphics::Vector2f,
+    // the length of the first child when the drag started
+    dragging: Option<f32>,
+    // what to go back to when uncollapsing
+    ratio_before_collapse: Option<f32>,
+    layout_cache: layout::LayoutCache,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+    _private: (),
+}
+
+impl<Data, First: Widget<Data>, Second: Widget<Data>, GetRatio: Fn(&mut Data) -> &mut f32> Split<Data, First, Second, GetRatio> {
+    // ratio is the current value in the model, and get_ratio is used to get at that same value when the divider is moved
+    pub fn new(direction: Direction, ratio: f32, get_ratio: GetRatio, first: First, second: Second) -> Self {
+        Self {
+            direction,
+            ratio,
+            get_ratio,
+            first,
+            second,
+            first_limits: (0.0, f32::INFINITY),
+            second_limits: (0.0, f32::INFINITY),
+            collapsible_side: Side::default(),
+            _phantom: PhantomData,
+        }
+    }
+    pub fn new_horizontal(ratio: f32, get_ratio: GetRatio, first: First, second: Second) -> Self {
+        Self::new(Direction::Horizontal, ratio, get_ratio, first, second)
+    }
+    pub fn new_vertical(ratio: f32, get_ratio: GetRatio, first: First, second: Second) -> Self {
+        Self::new(Direction::Vertical, ratio, get_ratio, first, second)
+    }
+
+    // the divider cannot be moved so that a child is smaller than its min or bigger than its max
+    pub fn with_first_limits(mut self, min: f32, max: f32) -> Self {
+        self.first_limits = (min, max);
+        self
+    }
+    pub fn with_second_limits(mut self, min: f32, max: f32) -> Self {
+        self.second_limits = (min, max);
+        self
+    }
+    // the side that gets hidden when the divider is double clicked
+    pub fn with_collapsible_side(mut self, side: Side) -> Self {
+        self.collapsible_side = side;
+        self
+    }
+}
+
+impl<Data, First: Widget<Data>, Second: Widget<Data>, GetRatio: Fn(&mut Data) -> &mut f32> Widget<Data> for Split<Data, First, Second, GetRatio> {
+    type ActualWidget = SplitActualWidget<Data, <First as Widget<Data>>::ActualWidget, <Second as Widget<Data>>::ActualWidget, GetRatio>;
+
+    fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        SplitActualWidget {
+            id: id_maker.next_id(),
+            direction: self.direction,
+            ratio: self.ratio,
+            get_ratio: self.get_ratio,
+            first: self.first.to_actual_widget(id_maker),
+            second: self.second.to_actual_widget(id_maker),
+            first_limits: self.first_limits,
+            second_limits: self.second_limits,
+            collapsible_side: self.collapsible_side,
+            content_length: 0.0,
+            first_length: 0.0,
+            size: graphics::Vector2f::new(0.0, 0.0),
+            dragging: None,
+            ratio_before_collapse: None,
+            layout_cache: layout::LayoutCache::new(),
+            _phantom: PhantomData,
+            _private: (),
+        }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        let cache = &mut actual_widget.layout_cache;
+        cache.update(&mut actual_widget.direction, self.direction);
+        cache.update(&mut actual_widget.ratio, self.ratio);
+        actual_widget.get_ratio = self.get_ratio;
+        cache.update(&mut actual_widget.first_limits, self.first_limits);
+        cache.update(&mut actual_widget.second_limits, self.second_limits);
+        cache.update(&mut actual_widget.collapsible_side, self.collapsible_side);
+        self.first.update_actual_widget(&mut actual_widget.first, id_maker);
+        self.second.update_actual_widget(&mut actual_widget.second, id_maker);
+    }
+}
+
+impl<Data, First: ActualWidget<Data>, Second: ActualWidget<Data>, GetRatio: Fn(&mut Data) -> &mut f32>
+    SplitActualWidget<Data, First, Second, GetRatio>
+{
+    fn collapsed_ratio(&self) -> f32 {
+        match self.collapsible_side {
+            Side::First => 0.0,
+            Side::Second => 1.0,
+        }
+    }
+
+    fn is_collapsed(&self) -> bool {
+        self.ratio == self.collapsed_ratio()
+    }
+
+    // the length of the first child if the divider was put at first_length, taking the limits of both children into account
+    fn clamp_first_length(&self, first_length: f32) -> f32 {
+        let (first_min, first_max) = self.first_limits;
+        let (second_min, second_max) = self.second_limits;
+        // if the limits cannot all be satisfied then the second child's limits lose
+        first_length
+            .max(self.content_length - second_max)
+            .min(self.content_length - second_min)
+            .max(first_min)
+            .min(first_max)
+            .clamp(0.0, self.content_length)
+    }
+
+    fn set_ratio(&mut self, data: &mut Data, ratio: f32) {
+        self.layout_cache.update(&mut self.ratio, ratio);
+        *(self.get_ratio)(data) = ratio;
+    }
+
+    fn move_divider_to(&mut self, data: &mut Data, first_length: f32) {
+        if self.content_length > 0.0 {
+            let ratio = self.clamp_first_length(first_length) / self.content_length;
+            self.set_ratio(data, ratio);
+        }
+    }
+
+    fn toggle_collapsed(&mut self, data: &mut Data) {
+        if self.is_collapsed() {
+            let ratio = self.ratio_before_collapse.take().unwrap_or(DEFAULT_RATIO);
+            self.set_ratio(data, ratio);
+        } else {
+            self.ratio_before_collapse = Some(self.ratio);
+            self.set_ratio(data, self.collapsed_ratio());
+        }
+    }
+
+    fn divider_rect(&self, top_left: graphics::Vector2f) -> graphics::FloatRect {
+        graphics::FloatRect::from_vecs(
+            top_left + self.direction.make_vector_in_direction(self.first_length, 0.0),
+            self.direction.make_vector_in_direction(DIVIDER_THICKNESS, self.direction.take_minor_component(self.size)),
+        )
+    }
+
+    fn second_top_left(&self, top_left: graphics::Vector2f) -> graphics::Vector2f {
+        top_left + self.direction.make_vector_in_direction(self.first_length + DIVIDER_THICKNESS, 0.0)
+    }
+
+    fn resize_cursor(&self) -> event::CursorShape {
+        match self.direction {
+            Direction::Horizontal => event::CursorShape::ResizeHorizontal,
+            Direction::Vertical => event::CursorShape::ResizeVertical,
+        }
+    }
+
+    fn intrinsic_size(&self, direction: Direction, cross_size: f32, measure: impl Fn(&dyn ActualWidget<Data>, Direction, f32) -> f32) -> f32 {
+        if direction == self.direction {
+            measure(&self.first, direction, cross_size) + DIVIDER_THICKNESS + measure(&self.second, direction, cross_size)
+        } else {
+            measure(&self.first, direction, cross_size).max(measure(&self.second, direction, cross_size))
+        }
+    }
+}
+
+impl<Data, First: ActualWidget<Data>, Second: ActualWidget<Data>, GetRatio: Fn(&mut Data) -> &mut f32> ActualWidget<Data>
+    for SplitActualWidget<Data, First, Second, GetRatio>
+{
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        let direction = self.direction;
+        let major_max = direction.take_major_component(sc.max);
+        let total_length = if major_max.is_finite() {
+            major_max
+        } else {
+            // there is no space to share so give both children the space they want
+            self.max_intrinsic_size(graphics_context, direction, direction.take_minor_component(sc.max))
+        };
+        self.content_length = (total_length - DIVIDER_THICKNESS).max(0.0);
+        self.first_length = if self.is_collapsed() {
+            // collapsing ignores the limits
+            self.ratio * self.content_length
+        } else {
+            self.clamp_first_length(self.ratio * self.content_length)
+        };
+        let second_length = self.content_length - self.first_length;
+
+        let child_sc = |length: f32| layout::SizeConstraints {
+            min: direction.make_vector_in_direction(length, direction.take_minor_component(sc.min)),
+            max: direction.make_vector_in_direction(length, direction.take_minor_component(sc.max)),
+        };
+        layout::layout_child(&mut self.first, graphics_context, child_sc(self.first_length));
+        layout::layout_child(&mut self.second, graphics_context, child_sc(second_length));
+
+        let minor_size = direction.take_minor_component(self.first.size()).max(direction.take_minor_component(self.second.size()));
+        self.size = sc.clamp_size(direction.make_vector_in_direction(total_length, minor_size));
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.first.needs_layout() || self.second.needs_layout()
+    }
+
+    fn draw(
+        &self,
+        graphics_context: &graphics::GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
+    ) {
+        self.first.draw(graphics_context, target, top_left, hover, focus);
+        self.second.draw(graphics_context, target, self.second_top_left(top_left), hover, focus);
+
+        let mut divider = graphics::RectangleShape::from_rect(self.divider_rect(top_left));
+        // TODO: pick better colors for these
+        divider.set_fill_color(if self.dragging.is_some() || hover.contains(&self.id) {
+            graphics::Color::rgba(255, 255, 255, 150)
+        } else {
+            graphics::Color::rgba(255, 255, 255, 60)
+        });
+        if focus == Some(self.id) {
+            divider.set_outline_color(graphics::Color::rgb(100, 150, 255)); // TODO: pick a better color for this
+            divider.set_outline_thickness(-2.0);
+        }
+        target.draw(&divider);
+    }
+
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_> {
+        if self.divider_rect(top_left).contains(mouse) {
+            Box::new(std::iter::once((self.id, false)))
+        } else {
+            Box::new(self.first.find_hover(top_left, mouse).chain(self.second.find_hover(self.second_top_left(top_left), mouse)))
+        }
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        Box::new(self.first.find_focusable().chain(std::iter::once(self.id)).chain(self.second.find_focusable()))
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        if target == self.id {
+            Some(vec![self.id])
+        } else {
+            self.first.find_path(target).or_else(|| self.second.find_path(target))
+        }
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        if query.matches_id(self.id) {
+            Some(self.divider_rect(top_left))
+        } else {
+            self.first.find_rect(top_left, query).or_else(|| self.second.find_rect(self.second_top_left(top_left), query))
+        }
+    }
+
+    fn size(&self) -> graphics::Vector2f {
+        self.size
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.first.baseline()
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.intrinsic_size(direction, cross_size, |child, direction, cross_size| child.min_intrinsic_size(graphics_context, direction, cross_size))
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.intrinsic_size(direction, cross_size, |child, direction, cross_size| child.max_intrinsic_size(graphics_context, direction, cross_size))
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        if target == self.id {
+            self.handle_event(top_left, data, event, ctx);
+        }
+
+        let second_top_left = self.second_top_left(top_left);
+        self.first.dispatch_event(top_left, data, target, event, ctx);
+        self.second.dispatch_event(second_top_left, data, target, event, ctx);
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, data: &mut Data, event: event::Event, ctx: &mut event::EventContext) {
+        match event {
+            event::Event::MouseDown { button: sfml::window::mouse::Button::Left, click_count, .. } => {
+                if click_count == 2 {
+                    self.toggle_collapsed(data);
+                } else {
+                    self.dragging = Some(self.first_length);
+                    ctx.capture_pointer();
+                }
+                ctx.set_handled();
+            }
+            event::Event::MouseDown { .. } => {}
+            event::Event::MouseMoved(_) => {
+                if let (Some(drag_start_length), Some(drag)) = (self.dragging, ctx.drag()) {
+                    self.move_divider_to(data, drag_start_length + self.direction.take_major_component(drag.position - drag.start));
+                }
+                ctx.request_cursor(self.resize_cursor());
+                ctx.set_handled();
+            }
+            event::Event::MouseUp { button: sfml::window::mouse::Button::Left, .. } => {
+                if self.dragging.take().is_some() {
+                    ctx.set_handled();
+                }
+            }
+            event::Event::MouseUp { .. } => {}
+            event::Event::KeyPressed { code, scan: _, modifiers, repeat: _ } => {
+                let step = if modifiers.shift { KEYBOARD_STEP_SHIFT } else { KEYBOARD_STEP };
+                match (self.direction, code) {
+                    (Direction::Horizontal, Key::Left) | (Direction::Vertical, Key::Up) => self.move_divider_to(data, self.first_length - step),
+                    (Direction::Horizontal, Key::Right) | (Direction::Vertical, Key::Down) => self.move_divider_to(data, self.first_length + step),
+                    (_, Key::Home) => self.move_divider_to(data, 0.0),
+                    (_, Key::End) => self.move_divider_to(data, self.content_length),
+                    (_, Key::Enter) => self.toggle_collapsed(data),
+                    _ => return,
+                }
+                ctx.set_handled();
+            }
+            event::Event::KeyReleased { .. } => {}
+            event::Event::TextEntered(_) => {}
+            event::Event::MouseWheelScrolled { .. } => {}
+            event::Event::PointerEnter => {}
+            event::Event::PointerLeave => {}
+        }
+    }
+}
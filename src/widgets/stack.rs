@@ -0,0 +1,258 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use crate::{
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event::{Event, EventContext},
+    graphics,
+    layout::{self, Direction},
+    widgets::Widget,
+};
+
+// distances from the edges of the stack that a child is anchored to
+// a child with no anchors at all is not positioned: it goes in the top left corner and the stack is sized to fit it
+// a child anchored to both edges of an axis is stretched between them, otherwise it keeps its own size
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct StackItem {
+    pub left: Option<f32>,
+    pub top: Option<f32>,
+    pub right: Option<f32>,
+    pub bottom: Option<f32>,
+}
+
+impl StackItem {
+    pub fn unpositioned() -> Self {
+        Self::default()
+    }
+    // covers the whole stack
+    pub fn fill() -> Self {
+        Self { left: Some(0.0), top: Some(0.0), right: Some(0.0), bottom: Some(0.0) }
+    }
+
+    pub fn with_left(self, left: f32) -> Self {
+        Self { left: Some(left), ..self }
+    }
+    pub fn with_top(self, top: f32) -> Self {
+        Self { top: Some(top), ..self }
+    }
+    pub fn with_right(self, right: f32) -> Self {
+        Self { right: Some(right), ..self }
+    }
+    pub fn with_bottom(self, bottom: f32) -> Self {
+        Self { bottom: Some(bottom), ..self }
+    }
+
+    fn anchors(&self, direction: Direction) -> (Option<f32>, Option<f32>) {
+        match direction {
+            Direction::Horizontal => (self.left, self.right),
+            Direction::Vertical => (self.top, self.bottom),
+        }
+    }
+
+    fn is_positioned(&self) -> bool {
+        self.left.is_some() || self.top.is_some() || self.right.is_some() || self.bottom.is_some()
+    }
+}
+
+// draws children on top of each other, with later children on top of earlier ones
+pub struct Stack<Data, Child: Widget<Data>> {
+    children: Vec<(StackItem, Child)>,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+pub struct StackActualWidget<Data, Child: ActualWidget<Data>> {
+    children: Vec<(StackItem, graphics::Vector2f, Child)>,
+
+    own_size: graphics::Vector2f,
+    layout_cache: layout::LayoutCache,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+    _private: (),
+}
+
+impl<Data, Child: Widget<Data>> Stack<Data, Child> {
+    pub fn new(children: Vec<(StackItem, Child)>) -> Self {
+        Self { children, _phantom: PhantomData }
+    }
+}
+
+impl<Data, Child: Widget<Data>> Widget<Data> for Stack<Data, Child> {
+    type ActualWidget = StackActualWidget<Data, <Child as Widget<Data>>::ActualWidget>;
+
+    fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        StackActualWidget {
+            children: self
+                .children
+                .into_iter()
+                .map(|(item, child)| (item, graphics::Vector2f::new(0.0, 0.0), child.to_actual_widget(id_maker)))
+                .collect(),
+            own_size: graphics::Vector2f::new(0.0, 0.0),
+            layout_cache: layout::LayoutCache::new(),
+            _phantom: PhantomData,
+            _private: (),
+        }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        let cache = &mut actual_widget.layout_cache;
+        if self.children.len() != actual_widget.children.len() {
+            cache.mark_dirty();
+        }
+
+        let ro_children = std::mem::take(&mut actual_widget.children);
+        let ro_children_infinite = ro_children.into_iter().map(Some).chain(std::iter::repeat_with(|| None));
+
+        let new_ro_children = self
+            .children
+            .into_iter()
+            .zip(ro_children_infinite)
+            .map(|((item, widget), ro)| match ro {
+                Some((old_item, offset, mut ro)) => {
+                    if old_item != item {
+                        cache.mark_dirty();
+                    }
+                    widget.update_actual_widget(&mut ro, id_maker);
+                    (item, offset, ro)
+                }
+                None => (item, graphics::Vector2f::new(0.0, 0.0), widget.to_actual_widget(id_maker)),
+            })
+            .collect();
+
+        actual_widget.children = new_ro_children;
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> StackActualWidget<Data, Child> {
+    // big enough for every child along with the distances it is anchored at
+    fn intrinsic_size(&self, direction: Direction, cross_size: f32, measure: impl Fn(&Child, Direction, f32) -> f32) -> f32 {
+        self.children
+            .iter()
+            .map(|(item, _, child)| {
+                let (start, end) = item.anchors(direction);
+                let (cross_start, cross_end) = item.anchors(direction.perpendicular());
+                let child_cross_size = (cross_size - cross_start.unwrap_or(0.0) - cross_end.unwrap_or(0.0)).max(0.0);
+                start.unwrap_or(0.0) + measure(child, direction, child_cross_size) + end.unwrap_or(0.0)
+            })
+            .fold(0.0, f32::max)
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for StackActualWidget<Data, Child> {
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        let mut unpositioned_size: Option<graphics::Vector2f> = None;
+        for (item, offset, child) in &mut self.children {
+            if !item.is_positioned() {
+                layout::layout_child(child, graphics_context, sc.with_no_min());
+                *offset = graphics::Vector2f::new(0.0, 0.0);
+                let size = unpositioned_size.get_or_insert(graphics::Vector2f::new(0.0, 0.0));
+                *size = graphics::Vector2f::new(size.x.max(child.size().x), size.y.max(child.size().y));
+            }
+        }
+
+        self.own_size = match unpositioned_size {
+            Some(size) => sc.clamp_size(size),
+            // with nothing to size the stack to, take up all of the space that is available
+            None => sc.clamp_size(graphics::Vector2f::new(
+                if sc.max.x.is_finite() { sc.max.x } else { 0.0 },
+                if sc.max.y.is_finite() { sc.max.y } else { 0.0 },
+            )),
+        };
+
+        for (item, offset, child) in &mut self.children {
+            if item.is_positioned() {
+                let (min_x, max_x) = anchored_constraints(self.own_size.x, item.left, item.right);
+                let (min_y, max_y) = anchored_constraints(self.own_size.y, item.top, item.bottom);
+                layout::layout_child(
+                    child,
+                    graphics_context,
+                    layout::SizeConstraints { min: graphics::Vector2f::new(min_x, min_y), max: graphics::Vector2f::new(max_x, max_y) },
+                );
+                *offset = graphics::Vector2f::new(
+                    anchored_offset(self.own_size.x, child.size().x, item.left, item.right),
+                    anchored_offset(self.own_size.y, child.size().y, item.top, item.bottom),
+                );
+            }
+        }
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.children.iter().any(|(_, _, child)| child.needs_layout())
+    }
+
+    fn draw(
+        &self,
+        graphics_context: &graphics::GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
+    ) {
+        for (_, offset, child) in &self.children {
+            child.draw(graphics_context, target, top_left + *offset, hover, focus);
+        }
+    }
+
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_> {
+        // top-most child first so that the layers underneath it only get the mouse if it lets clicks pass through
+        Box::new(self.children.iter().rev().flat_map(move |(_, offset, child)| child.find_hover(top_left + *offset, mouse)))
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        Box::new(self.children.iter().flat_map(|(_, _, child)| child.find_focusable()))
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.children.iter().find_map(|(_, _, child)| child.find_path(target))
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.children.iter().find_map(|(_, offset, child)| child.find_rect(top_left + *offset, query))
+    }
+
+    fn size(&self) -> graphics::Vector2f {
+        self.own_size
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.children.iter().find_map(|(_, offset, child)| child.baseline().map(|baseline| offset.y + baseline))
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.intrinsic_size(direction, cross_size, |child, direction, cross_size| child.min_intrinsic_size(graphics_context, direction, cross_size))
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.intrinsic_size(direction, cross_size, |child, direction, cross_size| child.max_intrinsic_size(graphics_context, direction, cross_size))
+    }
+
+    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: Event, ctx: &mut EventContext) {
+        for (_, offset, child) in &mut self.children {
+            child.dispatch_event(top_left + *offset, data, target, event, ctx);
+        }
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: Event, _: &mut EventContext) {}
+}
+
+// the min and max size of a positioned child along one axis
+fn anchored_constraints(stack_size: f32, start: Option<f32>, end: Option<f32>) -> (f32, f32) {
+    match (start, end) {
+        (Some(start), Some(end)) => {
+            let size = (stack_size - start - end).max(0.0);
+            (size, size)
+        }
+        (Some(anchor), None) | (None, Some(anchor)) => (0.0, (stack_size - anchor).max(0.0)),
+        (None, None) => (0.0, stack_size),
+    }
+}
+
+fn anchored_offset(stack_size: f32, child_size: f32, start: Option<f32>, end: Option<f32>) -> f32 {
+    match (start, end) {
+        (Some(start), _) => start,
+        (None, Some(end)) => stack_size - end - child_size,
+        (None, None) => 0.0,
+    }
+}
@@ -3,9 +3,10 @@ use std::collections::HashSet;
 use sfml::graphics::Shape;
 
 use crate::{
-    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker},
-    event::{GeneralEvent, TargetedEvent},
-    graphics, layout,
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event::{Event, EventContext},
+    graphics,
+    layout::{self, Direction},
     widgets::Widget,
 };
 
@@ -18,6 +19,7 @@ pub struct TestRectActualWidget {
     color: graphics::Color,
     ideal_size: graphics::Vector2f,
     layout_size: graphics::Vector2f,
+    layout_cache: layout::LayoutCache,
     _private: (),
 }
 
@@ -36,6 +38,7 @@ impl<Data> Widget<Data> for TestRect {
             ideal_size: self.size,
             _private: (),
             layout_size: graphics::Vector2f::new(0.0, 0.0),
+            layout_cache: layout::LayoutCache::new(),
             id: id_maker.next_id(),
         }
     }
@@ -43,13 +46,21 @@ impl<Data> Widget<Data> for TestRect {
     fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, _: &mut ActualWidgetIdMaker) {
         // TODO: animate?
         actual_widget.color = self.color;
-        actual_widget.ideal_size = self.size;
+        actual_widget.layout_cache.update(&mut actual_widget.ideal_size, self.size);
     }
 }
 
 impl<Data> ActualWidget<Data> for TestRectActualWidget {
     fn layout(&mut self, _: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.layout_cache.is_dirty() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
         self.layout_size = sc.clamp_size(self.ideal_size);
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty()
     }
 
     fn draw(
@@ -58,6 +69,7 @@ impl<Data> ActualWidget<Data> for TestRectActualWidget {
         target: &mut dyn graphics::RenderTarget,
         top_left: graphics::Vector2f,
         hover: &HashSet<ActualWidgetId>,
+        _: Option<ActualWidgetId>,
     ) {
         let rect = graphics::FloatRect::from_vecs(top_left, self.layout_size);
         let mut rect_shape = graphics::RectangleShape::from_rect(rect);
@@ -75,16 +87,43 @@ impl<Data> ActualWidget<Data> for TestRectActualWidget {
         Box::new(if graphics::FloatRect::from_vecs(top_left, self.layout_size).contains(mouse) { Some((self.id, false)) } else { None }.into_iter())
     }
 
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        if target == self.id {
+            Some(vec![self.id])
+        } else {
+            None
+        }
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        if query.matches_id(self.id) {
+            Some(graphics::FloatRect::from_vecs(top_left, self.layout_size))
+        } else {
+            None
+        }
+    }
+
     fn size(&self) -> graphics::Vector2f {
         self.layout_size
     }
 
-    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: TargetedEvent) {
+    fn min_intrinsic_size(&self, _: &graphics::GraphicsContext, direction: Direction, _: f32) -> f32 {
+        direction.take_major_component(self.ideal_size)
+    }
+
+    fn max_intrinsic_size(&self, _: &graphics::GraphicsContext, direction: Direction, _: f32) -> f32 {
+        direction.take_major_component(self.ideal_size)
+    }
+
+    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: Event, ctx: &mut EventContext) {
         if target == self.id {
-            self.targeted_event(top_left, data, event);
+            self.handle_event(top_left, data, event, ctx);
         }
     }
 
-    fn targeted_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: TargetedEvent) {}
-    fn general_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: GeneralEvent) {}
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: Event, _: &mut EventContext) {}
 }
@@ -0,0 +1,378 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use sfml::{
+    graphics::{Font, Shape, Transformable},
+    window::{clipboard, Key},
+};
+
+use crate::{
+    actual_widget::{util, ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event, graphics,
+    layout::{self, Direction},
+    widgets::Widget,
+};
+
+const PADDING: f32 = 4.0;
+
+pub struct TextField<Data, GetFont: Fn(&graphics::Fonts) -> &Font, GetText: Fn(&mut Data) -> &mut String> {
+    text: String,
+    get_font: GetFont,
+    font_size: u32,
+    get_text: GetText,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+pub struct TextFieldActualWidget<Data, GetFont: Fn(&graphics::Fonts) -> &Font, GetText: Fn(&mut Data) -> &mut String> {
+    id: ActualWidgetId,
+    text: String,
+    get_font: GetFont,
+    font_size: u32,
+    get_text: GetText,
+
+    // both of these are char indexes and not byte indexes because that is what sfml uses
+    // the selection is the range between the anchor and the caret
+    caret: usize,
+    anchor: usize,
+
+    // x position of each caret position, calculated during layout
+    caret_xs: Vec<f32>,
+    scroll: f32,
+    size: graphics::Vector2f,
+    layout_cache: layout::LayoutCache,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+    _private: (),
+}
+
+impl<Data, GetFont: Fn(&graphics::Fonts) -> &Font, GetText: Fn(&mut Data) -> &mut String> TextField<Data, GetFont, GetText> {
+    // text is the current contents of the string in the model, and get_text is used to get at that same string when it needs to be edited
+    pub fn new(text: String, get_font: GetFont, font_size: u32, get_text: GetText) -> Self {
+        Self { text, get_font, font_size, get_text, _phantom: PhantomData }
+    }
+}
+
+impl<Data, GetFont: Fn(&graphics::Fonts) -> &Font, GetText: Fn(&mut Data) -> &mut String> Widget<Data> for TextField<Data, GetFont, GetText> {
+    type ActualWidget = TextFieldActualWidget<Data, GetFont, GetText>;
+
+    fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        let len = self.text.chars().count();
+        TextFieldActualWidget {
+            id: id_maker.next_id(),
+            text: self.text,
+            get_font: self.get_font,
+            font_size: self.font_size,
+            get_text: self.get_text,
+            caret: len,
+            anchor: len,
+            caret_xs: Vec::new(),
+            scroll: 0.0,
+            size: graphics::Vector2f::new(0.0, 0.0),
+            layout_cache: layout::LayoutCache::new(),
+            _phantom: PhantomData,
+            _private: (),
+        }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, _: &mut ActualWidgetIdMaker) {
+        // the text could have been changed by something other than this text field
+        let len = self.text.chars().count();
+        actual_widget.caret = actual_widget.caret.min(len);
+        actual_widget.anchor = actual_widget.anchor.min(len);
+
+        actual_widget.layout_cache.update(&mut actual_widget.text, self.text);
+        actual_widget.layout_cache.update_fn(&mut actual_widget.get_font, self.get_font);
+        actual_widget.layout_cache.update(&mut actual_widget.font_size, self.font_size);
+        actual_widget.get_text = self.get_text;
+    }
+}
+
+impl<Data, GetFont: Fn(&graphics::Fonts) -> &Font, GetText: Fn(&mut Data) -> &mut String> TextFieldActualWidget<Data, GetFont, GetText> {
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn selection(&self) -> (usize, usize) {
+        (self.caret.min(self.anchor), self.caret.max(self.anchor))
+    }
+
+    fn selected_text(&self) -> &str {
+        let (start, end) = self.selection();
+        &self.text[byte_index(&self.text, start)..byte_index(&self.text, end)]
+    }
+
+    // the caret decides where the text is scrolled to during layout, so these mark the layout dirty
+    fn move_caret(&mut self, new_caret: usize, extend_selection: bool) {
+        self.caret = new_caret;
+        if !extend_selection {
+            self.anchor = new_caret;
+        }
+        self.layout_cache.mark_dirty();
+    }
+
+    fn select(&mut self, anchor: usize, caret: usize) {
+        self.anchor = anchor;
+        self.caret = caret;
+        self.layout_cache.mark_dirty();
+    }
+
+    fn replace_selection(&mut self, data: &mut Data, replacement: &str) {
+        let (start, end) = self.selection();
+        self.text.replace_range(byte_index(&self.text, start)..byte_index(&self.text, end), replacement);
+        (self.get_text)(data).clone_from(&self.text);
+
+        self.move_caret(start + replacement.chars().count(), false);
+    }
+
+    // the range of the run of word characters (or of non word characters) around a caret position
+    fn word_around(&self, caret: usize) -> (usize, usize) {
+        let chars: Vec<char> = self.text.chars().collect();
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+        let Some(&around) = chars.get(caret).or_else(|| caret.checked_sub(1).and_then(|before| chars.get(before))) else {
+            return (caret, caret);
+        };
+        let kind = is_word_char(around);
+
+        let start = chars[..caret].iter().rposition(|c| is_word_char(*c) != kind).map_or(0, |index| index + 1);
+        let end = chars[caret..].iter().position(|c| is_word_char(*c) != kind).map_or(chars.len(), |index| caret + index);
+        (start, end)
+    }
+
+    fn caret_index_at(&self, x: f32) -> usize {
+        // caret_xs might be out of date if the text was changed since the last layout
+        let closest = (0..self.caret_xs.len()).min_by(|a, b| (self.caret_xs[*a] - x).abs().total_cmp(&(self.caret_xs[*b] - x).abs()));
+        closest.unwrap_or(0).min(self.len())
+    }
+
+    // big enough to show all of the text without scrolling
+    fn natural_size(&self, graphics_context: &graphics::GraphicsContext) -> graphics::Vector2f {
+        let font = (self.get_font)(&graphics_context.fonts);
+        let text = graphics::Text::new(&self.text, font, self.font_size);
+        graphics::Vector2f::new(text.find_character_pos(self.len()).x, font.line_spacing(self.font_size))
+            + graphics::Vector2f::new(PADDING, PADDING) * 2.0
+    }
+}
+
+impl<Data, GetFont: Fn(&graphics::Fonts) -> &Font, GetText: Fn(&mut Data) -> &mut String> ActualWidget<Data>
+    for TextFieldActualWidget<Data, GetFont, GetText>
+{
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        let font = (self.get_font)(&graphics_context.fonts);
+        let text = graphics::Text::new(&self.text, font, self.font_size);
+        self.caret_xs = (0..=self.len()).map(|index| text.find_character_pos(index).x).collect();
+        let text_width = self.caret_xs[self.len()];
+        self.size =
+            sc.clamp_size(graphics::Vector2f::new(text_width, font.line_spacing(self.font_size)) + graphics::Vector2f::new(PADDING, PADDING) * 2.0);
+
+        // scroll so that the caret is always visible
+        let visible_width = (self.size.x - PADDING * 2.0).max(0.0);
+        let caret_x = self.caret_xs[self.caret];
+        if caret_x < self.scroll {
+            self.scroll = caret_x;
+        } else if caret_x > self.scroll + visible_width {
+            self.scroll = caret_x - visible_width;
+        }
+        self.scroll = self.scroll.clamp(0.0, (text_width - visible_width).max(0.0));
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty()
+    }
+
+    fn draw(
+        &self,
+        graphics_context: &graphics::GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        _: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
+    ) {
+        let focused = focus == Some(self.id);
+
+        let mut background = graphics::RectangleShape::from_rect(graphics::FloatRect::from_vecs(top_left, self.size));
+        background.set_fill_color(graphics::Color::rgb(30, 30, 30)); // TODO: control text field colors
+        background.set_outline_color(if focused { graphics::Color::rgb(100, 150, 255) } else { graphics::Color::rgb(80, 80, 80) });
+        background.set_outline_thickness(-1.0);
+        target.draw(&background);
+
+        let inner_rect = graphics::FloatRect::from_vecs(
+            top_left + graphics::Vector2f::new(PADDING, PADDING),
+            graphics::Vector2f::new((self.size.x - PADDING * 2.0).max(0.0), (self.size.y - PADDING * 2.0).max(0.0)),
+        );
+        util::clip(graphics_context, target, inner_rect, |target, top_left| {
+            let font = (self.get_font)(&graphics_context.fonts);
+            let mut text = graphics::Text::new(&self.text, font, self.font_size);
+            text.set_position(top_left - graphics::Vector2f::new(self.scroll, 0.0));
+            text.set_fill_color(graphics::Color::WHITE); // TODO: control text color
+
+            let (selection_start, selection_end) = self.selection();
+            if focused && selection_start != selection_end {
+                let start_x = text.find_character_pos(selection_start).x;
+                let end_x = text.find_character_pos(selection_end).x;
+                let mut selection = graphics::RectangleShape::from_rect(graphics::FloatRect::new(
+                    start_x,
+                    top_left.y,
+                    end_x - start_x,
+                    font.line_spacing(self.font_size),
+                ));
+                selection.set_fill_color(graphics::Color::rgb(50, 80, 140));
+                target.draw(&selection);
+            }
+
+            target.draw(&text);
+
+            if focused {
+                let caret_x = text.find_character_pos(self.caret).x;
+                let mut caret =
+                    graphics::RectangleShape::from_rect(graphics::FloatRect::new(caret_x, top_left.y, 1.0, font.line_spacing(self.font_size)));
+                caret.set_fill_color(graphics::Color::WHITE);
+                target.draw(&caret);
+            }
+        });
+    }
+
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)>> {
+        Box::new(if graphics::FloatRect::from_vecs(top_left, self.size).contains(mouse) { Some((self.id, false)) } else { None }.into_iter())
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId>> {
+        Box::new(std::iter::once(self.id))
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        if target == self.id {
+            Some(vec![self.id])
+        } else {
+            None
+        }
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        if query.matches_id(self.id) {
+            Some(graphics::FloatRect::from_vecs(top_left, self.size()))
+        } else {
+            None
+        }
+    }
+
+    fn size(&self) -> graphics::Vector2f {
+        self.size
+    }
+
+    // the text is drawn inside of the padding, and sfml puts its baseline one character size below where it is drawn
+    fn baseline(&self) -> Option<f32> {
+        Some(PADDING + self.font_size as f32)
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, _: f32) -> f32 {
+        match direction {
+            // text that does not fit gets scrolled
+            Direction::Horizontal => PADDING * 2.0,
+            Direction::Vertical => self.natural_size(graphics_context).y,
+        }
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, _: f32) -> f32 {
+        direction.take_major_component(self.natural_size(graphics_context))
+    }
+
+    fn dispatch_event(
+        &mut self,
+        top_left: graphics::Vector2f,
+        data: &mut Data,
+        target: ActualWidgetId,
+        event: event::Event,
+        ctx: &mut event::EventContext,
+    ) {
+        if target == self.id {
+            self.handle_event(top_left, data, event, ctx);
+        }
+    }
+
+    fn handle_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, event: event::Event, ctx: &mut event::EventContext) {
+        match event {
+            event::Event::MouseDown { button: sfml::window::mouse::Button::Left, position: mouse_position, modifiers, click_count } => {
+                let caret = self.caret_index_at(mouse_position.x - top_left.x - PADDING + self.scroll);
+                match click_count {
+                    1 => self.move_caret(caret, modifiers.shift),
+                    // double click selects a word
+                    2 => {
+                        let (word_start, word_end) = self.word_around(caret);
+                        self.select(word_start, word_end);
+                    }
+                    // triple click selects everything
+                    _ => self.select(0, self.len()),
+                }
+                ctx.set_handled();
+            }
+            event::Event::MouseDown { .. } => {}
+            event::Event::MouseMoved(_) => ctx.request_cursor(event::CursorShape::IBeam),
+            event::Event::MouseUp { .. } => {}
+            event::Event::KeyPressed { code, scan: _, modifiers: event::Modifiers { alt: _, ctrl, shift, system: _ }, repeat: _ } => {
+                match code {
+                    Key::Left => {
+                        let (selection_start, selection_end) = self.selection();
+                        if selection_start != selection_end && !shift {
+                            self.move_caret(selection_start, false);
+                        } else {
+                            self.move_caret(self.caret.saturating_sub(1), shift);
+                        }
+                    }
+                    Key::Right => {
+                        let (selection_start, selection_end) = self.selection();
+                        if selection_start != selection_end && !shift {
+                            self.move_caret(selection_end, false);
+                        } else {
+                            self.move_caret((self.caret + 1).min(self.len()), shift);
+                        }
+                    }
+                    Key::Home => self.move_caret(0, shift),
+                    Key::End => self.move_caret(self.len(), shift),
+                    Key::Backspace => {
+                        if self.caret == self.anchor {
+                            self.anchor = self.caret.saturating_sub(1);
+                        }
+                        self.replace_selection(data, "");
+                    }
+                    Key::Delete => {
+                        if self.caret == self.anchor {
+                            self.anchor = (self.caret + 1).min(self.len());
+                        }
+                        self.replace_selection(data, "");
+                    }
+                    Key::A if ctrl => self.select(0, self.len()),
+                    Key::C if ctrl => clipboard::set_string(self.selected_text()),
+                    Key::X if ctrl => {
+                        clipboard::set_string(self.selected_text());
+                        self.replace_selection(data, "");
+                    }
+                    Key::V if ctrl => {
+                        // this is a single line text field so newlines cannot be pasted in
+                        let pasted: String = clipboard::get_string().chars().filter(|c| !c.is_control()).collect();
+                        self.replace_selection(data, &pasted);
+                    }
+                    _ => return,
+                }
+                ctx.set_handled();
+            }
+            event::Event::KeyReleased { .. } => {}
+            event::Event::TextEntered(ch) => {
+                // control characters (backspace, tab, enter, ctrl+letter, ...) are handled through KeyPressed or not at all
+                if !ch.is_control() {
+                    self.replace_selection(data, ch.encode_utf8(&mut [0; 4]));
+                    ctx.set_handled();
+                }
+            }
+            event::Event::MouseWheelScrolled { .. } => {}
+            event::Event::PointerEnter => {}
+            event::Event::PointerLeave => {}
+        }
+    }
+}
+
+fn byte_index(text: &str, char_index: usize) -> usize {
+    text.char_indices().nth(char_index).map_or(text.len(), |(byte_index, _)| byte_index)
+}
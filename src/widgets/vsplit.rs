@@ -1,9 +1,10 @@
 use std::{collections::HashSet, marker::PhantomData};
 
 use crate::{
-    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker},
-    event::{GeneralEvent, TargetedEvent},
-    graphics, layout,
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event::{Event, EventContext},
+    graphics,
+    layout::{self, Direction},
     widgets::Widget,
 };
 
@@ -19,6 +20,7 @@ pub struct VSplitActualWidget<Data, Left: ActualWidget<Data>, Right: ActualWidge
     right: Right,
 
     size: graphics::Vector2f,
+    layout_cache: layout::LayoutCache,
 
     _phantom: PhantomData<fn(&mut Data)>,
     _private: (),
@@ -38,6 +40,7 @@ impl<Data, Left: Widget<Data>, Right: Widget<Data>> Widget<Data> for VSplit<Data
             left: self.left.to_actual_widget(id_maker),
             right: self.right.to_actual_widget(id_maker),
             size: graphics::Vector2f::new(0.0, 0.0),
+            layout_cache: layout::LayoutCache::new(),
             _phantom: PhantomData,
             _private: (),
         }
@@ -48,15 +51,33 @@ impl<Data, Left: Widget<Data>, Right: Widget<Data>> Widget<Data> for VSplit<Data
         self.right.update_actual_widget(&mut actual_widget.right, id_maker);
     }
 }
+impl<Data, Left: ActualWidget<Data>, Right: ActualWidget<Data>> VSplitActualWidget<Data, Left, Right> {
+    fn intrinsic_size(&self, direction: Direction, cross_size: f32, measure: impl Fn(&dyn ActualWidget<Data>, Direction, f32) -> f32) -> f32 {
+        match direction {
+            // both halves are always the same width
+            Direction::Horizontal => measure(&self.left, direction, cross_size).max(measure(&self.right, direction, cross_size)) * 2.0,
+            Direction::Vertical => measure(&self.left, direction, cross_size / 2.0).max(measure(&self.right, direction, cross_size / 2.0)),
+        }
+    }
+}
+
 impl<Data, Left: ActualWidget<Data>, Right: ActualWidget<Data>> ActualWidget<Data> for VSplitActualWidget<Data, Left, Right> {
     fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
         let half_sc = layout::SizeConstraints {
             min: graphics::Vector2f::new(sc.min.x / 2.0, sc.min.y),
             max: graphics::Vector2f::new(sc.max.x / 2.0, sc.max.y),
         };
-        self.left.layout(graphics_context, half_sc);
-        self.right.layout(graphics_context, half_sc);
+        layout::layout_child(&mut self.left, graphics_context, half_sc);
+        layout::layout_child(&mut self.right, graphics_context, half_sc);
         self.size = sc.clamp_size(self.left.size() + self.right.size());
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.left.needs_layout() || self.right.needs_layout()
     }
 
     fn draw(
@@ -65,30 +86,50 @@ impl<Data, Left: ActualWidget<Data>, Right: ActualWidget<Data>> ActualWidget<Dat
         target: &mut dyn graphics::RenderTarget,
         top_left: graphics::Vector2f,
         hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
     ) {
-        self.left.draw(graphics_context, target, top_left, hover);
-        self.right.draw(graphics_context, target, top_left + graphics::Vector2f::new(self.left.size().x, 0.0), hover);
+        self.left.draw(graphics_context, target, top_left, hover, focus);
+        self.right.draw(graphics_context, target, top_left + graphics::Vector2f::new(self.left.size().x, 0.0), hover, focus);
     }
 
     fn size(&self) -> graphics::Vector2f {
         self.size
     }
 
+    fn baseline(&self) -> Option<f32> {
+        self.left.baseline()
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.intrinsic_size(direction, cross_size, |child, direction, cross_size| child.min_intrinsic_size(graphics_context, direction, cross_size))
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.intrinsic_size(direction, cross_size, |child, direction, cross_size| child.max_intrinsic_size(graphics_context, direction, cross_size))
+    }
+
     fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<(dyn Iterator<Item = (ActualWidgetId, bool)> + '_)> {
         Box::new(
             self.left.find_hover(top_left, mouse).chain(self.right.find_hover(top_left + graphics::Vector2f::new(self.left.size().x, 0.0), mouse)),
         )
     }
 
-    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: TargetedEvent) {
-        self.left.dispatch_event(top_left, data, target, event);
-        self.right.dispatch_event(top_left + graphics::Vector2f::new(self.left.size().x, 0.0), data, target, event);
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        Box::new(self.left.find_focusable().chain(self.right.find_focusable()))
     }
 
-    fn targeted_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: TargetedEvent) {}
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.left.find_path(target).or_else(|| self.right.find_path(target))
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.left.find_rect(top_left, query).or_else(|| self.right.find_rect(top_left + graphics::Vector2f::new(self.left.size().x, 0.0), query))
+    }
 
-    fn general_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, event: GeneralEvent) {
-        self.left.general_event(top_left, data, event);
-        self.right.general_event(top_left + graphics::Vector2f::new(self.left.size().x, 0.0), data, event);
+    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: Event, ctx: &mut EventContext) {
+        self.left.dispatch_event(top_left, data, target, event, ctx);
+        self.right.dispatch_event(top_left + graphics::Vector2f::new(self.left.size().x, 0.0), data, target, event, ctx);
     }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: Event, _: &mut EventContext) {}
 }
@@ -0,0 +1,286 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use crate::{
+    actual_widget::{ActualWidget, ActualWidgetId, ActualWidgetIdMaker, WidgetQuery},
+    event::{Event, EventContext},
+    graphics,
+    layout::{self, Direction},
+    widgets::{
+        flex::{_layout, MainAxisAlignment},
+        Widget,
+    },
+};
+
+// how the items in a run are placed perpendicular to the direction of the wrap
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum RunCrossAlignment {
+    #[default]
+    Start,
+    Center,
+    End,
+}
+
+// lays children out in a row (or column), starting a new row once there is no more space left in the current one
+pub struct Wrap<Data, Child: Widget<Data>> {
+    direction: Direction,
+    spacing: f32,
+    run_spacing: f32,
+    run_alignment: MainAxisAlignment,
+    run_cross_alignment: RunCrossAlignment,
+    children: Vec<Child>,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+}
+pub struct WrapActualWidget<Data, Child: ActualWidget<Data>> {
+    direction: Direction,
+    spacing: f32,
+    run_spacing: f32,
+    run_alignment: MainAxisAlignment,
+    run_cross_alignment: RunCrossAlignment,
+    children: Vec<(graphics::Vector2f, Child)>,
+
+    own_size: graphics::Vector2f,
+    layout_cache: layout::LayoutCache,
+
+    _phantom: PhantomData<fn(&mut Data)>,
+    _private: (),
+}
+
+impl<Data, Child: Widget<Data>> Wrap<Data, Child> {
+    pub fn new(direction: Direction, children: Vec<Child>) -> Self {
+        Self {
+            direction,
+            spacing: 0.0,
+            run_spacing: 0.0,
+            run_alignment: MainAxisAlignment::default(),
+            run_cross_alignment: RunCrossAlignment::default(),
+            children,
+            _phantom: PhantomData,
+        }
+    }
+    pub fn new_horizontal(children: Vec<Child>) -> Self {
+        Self::new(Direction::Horizontal, children)
+    }
+    pub fn new_vertical(children: Vec<Child>) -> Self {
+        Self::new(Direction::Vertical, children)
+    }
+
+    // space between neighboring children in the same run
+    pub fn with_spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+    // space between neighboring runs
+    pub fn with_run_spacing(mut self, run_spacing: f32) -> Self {
+        self.run_spacing = run_spacing;
+        self
+    }
+    // how the children in each run are placed along the run
+    pub fn with_run_alignment(mut self, run_alignment: MainAxisAlignment) -> Self {
+        self.run_alignment = run_alignment;
+        self
+    }
+    pub fn with_run_cross_alignment(mut self, run_cross_alignment: RunCrossAlignment) -> Self {
+        self.run_cross_alignment = run_cross_alignment;
+        self
+    }
+}
+
+impl<Data, Child: Widget<Data>> Widget<Data> for Wrap<Data, Child> {
+    type ActualWidget = WrapActualWidget<Data, <Child as Widget<Data>>::ActualWidget>;
+
+    fn to_actual_widget(self, id_maker: &mut ActualWidgetIdMaker) -> Self::ActualWidget {
+        WrapActualWidget {
+            direction: self.direction,
+            spacing: self.spacing,
+            run_spacing: self.run_spacing,
+            run_alignment: self.run_alignment,
+            run_cross_alignment: self.run_cross_alignment,
+            children: self.children.into_iter().map(|child| (graphics::Vector2f::new(0.0, 0.0), child.to_actual_widget(id_maker))).collect(),
+            own_size: graphics::Vector2f::new(0.0, 0.0),
+            layout_cache: layout::LayoutCache::new(),
+            _phantom: PhantomData,
+            _private: (),
+        }
+    }
+
+    fn update_actual_widget(self, actual_widget: &mut Self::ActualWidget, id_maker: &mut ActualWidgetIdMaker) {
+        let cache = &mut actual_widget.layout_cache;
+        cache.update(&mut actual_widget.direction, self.direction);
+        cache.update(&mut actual_widget.spacing, self.spacing);
+        cache.update(&mut actual_widget.run_spacing, self.run_spacing);
+        cache.update(&mut actual_widget.run_alignment, self.run_alignment);
+        cache.update(&mut actual_widget.run_cross_alignment, self.run_cross_alignment);
+        if self.children.len() != actual_widget.children.len() {
+            cache.mark_dirty();
+        }
+
+        let ro_children = std::mem::take(&mut actual_widget.children);
+        let ro_children_infinite = ro_children.into_iter().map(Some).chain(std::iter::repeat_with(|| None));
+
+        let new_ro_children = self
+            .children
+            .into_iter()
+            .zip(ro_children_infinite)
+            .map(|(widget, ro)| match ro {
+                Some((offset, mut ro)) => {
+                    widget.update_actual_widget(&mut ro, id_maker);
+                    (offset, ro)
+                }
+                None => (graphics::Vector2f::new(0.0, 0.0), widget.to_actual_widget(id_maker)),
+            })
+            .collect();
+
+        actual_widget.children = new_ro_children;
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> WrapActualWidget<Data, Child> {
+    fn intrinsic_size(&self, direction: Direction, cross_size: f32, measure: impl Fn(&Child, Direction, f32) -> f32) -> f32 {
+        if direction == self.direction {
+            // the children are all in one run
+            let total: f32 = self.children.iter().map(|(_, child)| measure(child, direction, f32::INFINITY)).sum();
+            total + self.spacing * self.children.len().saturating_sub(1) as f32
+        } else {
+            // the runs are as long as cross_size allows
+            let runs = split_into_runs(
+                self.spacing,
+                cross_size,
+                self.children.iter().map(|(_, child)| {
+                    let child_major = measure(child, self.direction, f32::INFINITY);
+                    (child_major, measure(child, direction, child_major))
+                }),
+            );
+            runs.iter().map(|(_, _, _, run_minor)| *run_minor).sum::<f32>() + self.run_spacing * runs.len().saturating_sub(1) as f32
+        }
+    }
+}
+
+impl<Data, Child: ActualWidget<Data>> ActualWidget<Data> for WrapActualWidget<Data, Child> {
+    fn layout(&mut self, graphics_context: &graphics::GraphicsContext, sc: layout::SizeConstraints) {
+        if !self.needs_layout() && self.layout_cache.was_laid_out_with(sc) {
+            return;
+        }
+        let direction = self.direction;
+        let major_max = direction.take_major_component(sc.max);
+
+        for (_, child) in &mut self.children {
+            layout::layout_child(child, graphics_context, sc.with_no_min());
+        }
+
+        let runs = split_into_runs(
+            self.spacing,
+            major_max,
+            self.children.iter().map(|(_, child)| (direction.take_major_component(child.size()), direction.take_minor_component(child.size()))),
+        );
+
+        let widest_run = runs.iter().map(|(_, _, run_major, _)| *run_major).fold(0.0, f32::max);
+        let total_minor = runs.iter().map(|(_, _, _, run_minor)| *run_minor).sum::<f32>() + self.run_spacing * runs.len().saturating_sub(1) as f32;
+        self.own_size = sc.clamp_size(direction.make_vector_in_direction(
+            // like flex, anything other than packing at the start needs free space to distribute
+            if self.run_alignment != MainAxisAlignment::Start && major_max.is_finite() { major_max } else { widest_run },
+            total_minor,
+        ));
+        let own_major = direction.take_major_component(self.own_size);
+
+        let mut minor_offset = 0.0;
+        for (start, end, run_major, run_minor) in runs {
+            let (leading_space, space_between) = _layout::distribute_free_space(self.run_alignment, (own_major - run_major).max(0.0), end - start);
+            let mut major_offset = leading_space;
+            for (offset, child) in &mut self.children[start..end] {
+                let free_minor_space = run_minor - direction.take_minor_component(child.size());
+                let child_minor_offset = match self.run_cross_alignment {
+                    RunCrossAlignment::Start => 0.0,
+                    RunCrossAlignment::Center => free_minor_space / 2.0,
+                    RunCrossAlignment::End => free_minor_space,
+                };
+                *offset = direction.make_vector_in_direction(major_offset, minor_offset + child_minor_offset);
+                major_offset += direction.take_major_component(child.size()) + self.spacing + space_between;
+            }
+            minor_offset += run_minor + self.run_spacing;
+        }
+        self.layout_cache.laid_out(sc);
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.layout_cache.is_dirty() || self.children.iter().any(|(_, child)| child.needs_layout())
+    }
+
+    fn draw(
+        &self,
+        graphics_context: &graphics::GraphicsContext,
+        target: &mut dyn graphics::RenderTarget,
+        top_left: graphics::Vector2f,
+        hover: &HashSet<ActualWidgetId>,
+        focus: Option<ActualWidgetId>,
+    ) {
+        for (offset, child) in &self.children {
+            child.draw(graphics_context, target, top_left + *offset, hover, focus);
+        }
+    }
+
+    fn find_hover(&self, top_left: graphics::Vector2f, mouse: graphics::Vector2f) -> Box<dyn Iterator<Item = (ActualWidgetId, bool)> + '_> {
+        Box::new(self.children.iter().flat_map(move |(offset, child)| child.find_hover(top_left + *offset, mouse)))
+    }
+
+    fn find_focusable(&self) -> Box<dyn Iterator<Item = ActualWidgetId> + '_> {
+        Box::new(self.children.iter().flat_map(|(_, child)| child.find_focusable()))
+    }
+
+    fn find_path(&self, target: ActualWidgetId) -> Option<Vec<ActualWidgetId>> {
+        self.children.iter().find_map(|(_, child)| child.find_path(target))
+    }
+
+    fn find_rect(&self, top_left: graphics::Vector2f, query: WidgetQuery) -> Option<graphics::FloatRect> {
+        self.children.iter().find_map(|(offset, child)| child.find_rect(top_left + *offset, query))
+    }
+
+    fn size(&self) -> graphics::Vector2f {
+        self.own_size
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.children.iter().find_map(|(offset, child)| child.baseline().map(|baseline| offset.y + baseline))
+    }
+
+    fn min_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        if direction == self.direction {
+            // every child could go in a run by itself
+            self.children.iter().map(|(_, child)| child.min_intrinsic_size(graphics_context, direction, f32::INFINITY)).fold(0.0, f32::max)
+        } else {
+            self.intrinsic_size(direction, cross_size, |child, direction, cross_size| {
+                child.min_intrinsic_size(graphics_context, direction, cross_size)
+            })
+        }
+    }
+
+    fn max_intrinsic_size(&self, graphics_context: &graphics::GraphicsContext, direction: Direction, cross_size: f32) -> f32 {
+        self.intrinsic_size(direction, cross_size, |child, direction, cross_size| child.max_intrinsic_size(graphics_context, direction, cross_size))
+    }
+
+    fn dispatch_event(&mut self, top_left: graphics::Vector2f, data: &mut Data, target: ActualWidgetId, event: Event, ctx: &mut EventContext) {
+        for (offset, child) in &mut self.children {
+            child.dispatch_event(top_left + *offset, data, target, event, ctx);
+        }
+    }
+
+    fn handle_event(&mut self, _: graphics::Vector2f, _: &mut Data, _: Event, _: &mut EventContext) {}
+}
+
+// splits children (given as their major and minor sizes) into runs of at most major_max long
+// returns (index of first child, index past last child, major size, minor size) for each run
+fn split_into_runs(spacing: f32, major_max: f32, children: impl Iterator<Item = (f32, f32)>) -> Vec<(usize, usize, f32, f32)> {
+    let mut runs: Vec<(usize, usize, f32, f32)> = Vec::new();
+    for (index, (child_major, child_minor)) in children.enumerate() {
+        match runs.last_mut() {
+            // a child that is too big for a run by itself still gets a run of its own
+            Some((_, end, run_major, run_minor)) if *run_major + spacing + child_major <= major_max => {
+                *end = index + 1;
+                *run_major += spacing + child_major;
+                *run_minor = run_minor.max(child_minor);
+            }
+            _ => runs.push((index, index + 1, child_major, child_minor)),
+        }
+    }
+    runs
+}